@@ -0,0 +1,356 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    Data, DeriveInput, Error, Fields, GenericArgument, Ident, LitStr, PathArguments, Type,
+    parse_macro_input,
+};
+
+// #[derive(AocParse)] with #[aoc(pattern = "...")] generates a FromStr impl for a struct.
+// in the pattern `{name}` parses a field with its own FromStr, `{name:SEP}` splits into a
+// collection (a blank SEP splits on whitespace), `{name:(,)*}` reads whitespace separated
+// groups such as `(1,3) (2)` into a collection of collections, `{{` and `}}` are literal braces,
+// anything else must match exactly. fields left out of the pattern need #[aoc(skip)]
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field { name: String, kind: FieldKind },
+}
+
+#[derive(Debug, PartialEq)]
+enum FieldKind {
+    Value,
+    List {
+        sep: String,
+    },
+    Groups {
+        open: char,
+        sep: String,
+        close: char,
+    },
+}
+
+fn parse_pattern(pattern: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => return Err("unmatched `}` in pattern, use `}}` for a literal brace".into()),
+            '{' => {
+                let mut spec = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => spec.push(c),
+                        None => return Err(format!("unclosed `{{{}` in pattern", spec)),
+                    }
+                }
+                if matches!(segments.last(), Some(Segment::Field { .. })) && literal.is_empty() {
+                    return Err(format!(
+                        "`{{{}}}` directly follows another field, put a literal between them",
+                        spec
+                    ));
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(parse_field(&spec)?);
+            }
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+fn parse_field(spec: &str) -> Result<Segment, String> {
+    let (name, format) = match spec.split_once(':') {
+        Some((name, format)) => (name.trim(), Some(format)),
+        None => (spec.trim(), None),
+    };
+    if name.is_empty() || !name.chars().all(|c| c == '_' || c.is_alphanumeric()) {
+        return Err(format!("`{{{}}}` is not a valid field name", spec));
+    }
+    let kind = match format {
+        None => FieldKind::Value,
+        Some(format) => match format.strip_suffix('*') {
+            Some(group) => {
+                let chars: Vec<char> = group.chars().collect();
+                if chars.len() < 3 {
+                    return Err(format!(
+                        "group format `{}` needs an open char, a separator and a close char",
+                        format
+                    ));
+                }
+                FieldKind::Groups {
+                    open: chars[0],
+                    sep: chars[1..chars.len() - 1].iter().collect(),
+                    close: chars[chars.len() - 1],
+                }
+            }
+            None if format.is_empty() => {
+                return Err(format!("`{{{}}}` has an empty separator", spec));
+            }
+            None => FieldKind::List {
+                sep: format.to_string(),
+            },
+        },
+    };
+    Ok(Segment::Field {
+        name: name.to_string(),
+        kind,
+    })
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(input, "AocParse needs named fields")),
+        },
+        _ => return Err(Error::new_spanned(input, "AocParse only supports structs")),
+    };
+
+    let pattern = pattern_attr(input)?;
+    let segments =
+        parse_pattern(&pattern.value()).map_err(|message| Error::new(pattern.span(), message))?;
+
+    let mut steps = Vec::new();
+    let mut parsed: Vec<String> = Vec::new();
+    for (i, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Literal(text) => steps.push(quote! { __cursor.literal(#text)?; }),
+            Segment::Field { name, kind } => {
+                let field = fields
+                    .iter()
+                    .find(|f| f.ident.as_ref().is_some_and(|ident| ident == name))
+                    .ok_or_else(|| {
+                        Error::new(pattern.span(), format!("no field named `{}`", name))
+                    })?;
+                if parsed.contains(name) {
+                    return Err(Error::new(
+                        pattern.span(),
+                        format!("`{}` appears twice in the pattern", name),
+                    ));
+                }
+                parsed.push(name.clone());
+
+                let until = match segments.get(i + 1) {
+                    Some(Segment::Literal(text)) => quote! { ::std::option::Option::Some(#text) },
+                    _ => quote! { ::std::option::Option::None },
+                };
+                let ident = Ident::new(name, Span::call_site());
+                let ty = &field.ty;
+                let step = match kind {
+                    FieldKind::Value => quote! {
+                        let #ident: #ty = __cursor.value(#until, #name)?;
+                    },
+                    FieldKind::List { sep } => {
+                        let item = element_type(ty);
+                        quote! {
+                            let #ident: #ty = __cursor.list::<#ty, #item>(#until, #sep, #name)?;
+                        }
+                    }
+                    FieldKind::Groups { open, sep, close } => {
+                        let group = element_type(ty);
+                        let item = group_item_type(ty);
+                        quote! {
+                            let #ident: #ty = __cursor.groups::<#ty, #group, #item>(
+                                #until, #open, #sep, #close, #name,
+                            )?;
+                        }
+                    }
+                };
+                steps.push(step);
+            }
+        }
+    }
+
+    let mut inits = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        if is_skipped(field)? {
+            if parsed.iter().any(|name| ident == name) {
+                return Err(Error::new_spanned(
+                    field,
+                    "field is marked #[aoc(skip)] but used in the pattern",
+                ));
+            }
+            inits.push(quote! { #ident: ::std::default::Default::default() });
+        } else if parsed.iter().any(|name| ident == name) {
+            inits.push(quote! { #ident });
+        } else {
+            return Err(Error::new_spanned(
+                field,
+                format!(
+                    "`{}` is not in the pattern, add it or mark it #[aoc(skip)]",
+                    ident
+                ),
+            ));
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = ::aoc_rust::utils::parse_error::OffsetError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                let mut __cursor = ::aoc_rust::utils::aoc_parse::Cursor::new(s);
+                #(#steps)*
+                __cursor.end()?;
+                ::std::result::Result::Ok(Self { #(#inits),* })
+            }
+        }
+    })
+}
+
+fn pattern_attr(input: &DeriveInput) -> syn::Result<LitStr> {
+    let mut pattern = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("pattern") {
+                pattern = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `pattern = \"...\"`"))
+            }
+        })?;
+    }
+    pattern.ok_or_else(|| {
+        Error::new_spanned(
+            &input.ident,
+            "AocParse needs #[aoc(pattern = \"...\")] on the struct",
+        )
+    })
+}
+
+fn is_skipped(field: &syn::Field) -> syn::Result<bool> {
+    let mut skipped = false;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skipped = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `skip`"))
+            }
+        })?;
+    }
+    Ok(skipped)
+}
+
+// the T in Vec<T>, HashSet<T> and friends so list items don't rely on inference,
+// aliases like `type Button = HashSet<usize>` fall back to `_` and still infer from the impl
+fn element_type(ty: &Type) -> TokenStream2 {
+    if let Type::Path(path) = ty
+        && let Some(segment) = path.path.segments.last()
+        && let PathArguments::AngleBracketed(args) = &segment.arguments
+        && let Some(GenericArgument::Type(item)) = args.args.first()
+    {
+        return quote! { #item };
+    }
+    quote! { _ }
+}
+
+fn group_item_type(ty: &Type) -> TokenStream2 {
+    if let Type::Path(path) = ty
+        && let Some(segment) = path.path.segments.last()
+        && let PathArguments::AngleBracketed(args) = &segment.arguments
+        && let Some(GenericArgument::Type(group)) = args.args.first()
+    {
+        return element_type(group);
+    }
+    quote! { _ }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, kind: FieldKind) -> Segment {
+        Segment::Field {
+            name: name.to_string(),
+            kind,
+        }
+    }
+
+    fn literal(text: &str) -> Segment {
+        Segment::Literal(text.to_string())
+    }
+
+    #[test]
+    fn test_day10_pattern() {
+        assert_eq!(
+            parse_pattern("[{lights}] {buttons:(,)*} {{{voltages:,}}}"),
+            Ok(vec![
+                literal("["),
+                field("lights", FieldKind::Value),
+                literal("] "),
+                field(
+                    "buttons",
+                    FieldKind::Groups {
+                        open: '(',
+                        sep: ",".to_string(),
+                        close: ')'
+                    }
+                ),
+                literal(" {"),
+                field(
+                    "voltages",
+                    FieldKind::List {
+                        sep: ",".to_string()
+                    }
+                ),
+                literal("}"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_whitespace_list() {
+        assert_eq!(
+            parse_pattern("{name}: {children: }"),
+            Ok(vec![
+                field("name", FieldKind::Value),
+                literal(": "),
+                field(
+                    "children",
+                    FieldKind::List {
+                        sep: " ".to_string()
+                    }
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_rejects_bad_patterns() {
+        assert!(parse_pattern("{a}{b}").is_err());
+        assert!(parse_pattern("{a").is_err());
+        assert!(parse_pattern("a}").is_err());
+        assert!(parse_pattern("{}").is_err());
+        assert!(parse_pattern("{a:}").is_err());
+        assert!(parse_pattern("{a:()*}").is_err());
+    }
+}
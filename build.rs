@@ -0,0 +1,84 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+const YEARS_DIR: &str = "src/years";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", YEARS_DIR);
+
+    // only bake the inputs in when asked to, normal builds read them from disk
+    if env::var_os("CARGO_FEATURE_EMBEDDED_INPUTS").is_none() {
+        return;
+    }
+
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let mut entries: Vec<String> = Vec::new();
+
+    for year_dir in sorted_dirs(&manifest_dir.join(YEARS_DIR), "year") {
+        for day_dir in sorted_dirs(&year_dir, "day") {
+            for input in input_files(&day_dir) {
+                let relative = input
+                    .strip_prefix(&manifest_dir)
+                    .unwrap()
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                entries.push(format!(
+                    "    ({:?}, include_str!({:?})),",
+                    relative,
+                    input.display().to_string()
+                ));
+            }
+        }
+    }
+
+    let generated = format!(
+        "pub static EMBEDDED_INPUTS: &[(&str, &str)] = &[\n{}\n];\n",
+        entries.join("\n")
+    );
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("embedded_inputs.rs");
+    fs::write(out_path, generated).unwrap();
+}
+
+fn sorted_dirs(dir: &Path, prefix: &str) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_dir()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_prefix(prefix))
+                    .is_some_and(|rest| {
+                        !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit())
+                    })
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn input_files(day_dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(day_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| {
+                        name.starts_with("input")
+                            && (name.ends_with(".txt") || name.ends_with(".txt.enc"))
+                    })
+        })
+        .collect();
+    files.sort();
+    files
+}
@@ -11,43 +11,53 @@ INPUT_TXT = "input.txt"
 INPUT_TEST_TXT = "input_test.txt"
 YEARS_DIR = "src/years"
 
-YEAR_MOD_TEMPLATE = """use crate::{{solver::solver::Solver, utils::file_reader::StdFileReader}};
+YEAR_MOD_TEMPLATE = """use crate::{{
+    solver::solver::Solver,
+    utils::{{file_reader::default_reader, parse_error::InputError}},
+}};
 
 {mod_declarations}
 
-pub fn run_day(day: u32) {{
-    let reader = StdFileReader;
+pub fn run_day(day: u32) -> Result<(), InputError> {{
+    let reader = default_reader();
     match day {{
 {match_cases}
-        _ => println!("Day {{}} not implemented", day),
+        _ => {{
+            println!("Day {{}} not implemented", day);
+            Ok(())
+        }}
     }}
 }}
 """
 
-YEARS_MOD_TEMPLATE = """{mod_declarations}
+YEARS_MOD_TEMPLATE = """use crate::utils::parse_error::InputError;
 
-pub fn run_day(year: u32, day: u32) {{
+{mod_declarations}
+
+pub fn run_day(year: u32, day: u32) -> Result<(), InputError> {{
     match year {{
 {match_cases}
-        _ => println!("Year {{}} not implemented", year),
+        _ => {{
+            println!("Year {{}} not implemented", year);
+            Ok(())
+        }}
     }}
 }}
 """
 
-MATCH_CASE_TEMPLATE = """        {day} => {{
-            let mut solver =
-                day{day_padded}::Day{day_padded}::from_default_path(&reader, {year}, day).expect("Failed to load input");
-            solver.solve();
-        }}"""
+MATCH_CASE_TEMPLATE = """        {day} => day{day_padded}::Day{day_padded}::run(&reader, {year}, day),"""
 
-DAY_MOD_TEMPLATE = """use crate::{{solver::solver::Solver, utils::file_reader::FileReader}};
+DAY_MOD_TEMPLATE = """use crate::{{
+    solver::solver::Solver,
+    utils::{{file_reader::FileReader, input::Input, parse_error::InputError}},
+}};
 
 pub struct Day{day_padded} {{
-    data: String,
+    data: Input,
 }}
 
 impl Solver<u32> for Day{day_padded} {{
-    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, String> {{
+    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, InputError> {{
         let data = reader.read_file(file_path)?;
         Ok(Day{day_padded} {{ data }})
     }}
@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(name = "aoc-rust", about = "Advent of Code solutions")]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    /// Year to run before dropping into the interactive prompt
+    pub year: Option<String>,
+    /// Day to run before dropping into the interactive prompt
+    pub day: Option<String>,
+    /// Print intermediate state such as grid overlays to stderr
+    #[arg(long, global = true)]
+    pub debug: bool,
+    /// Directory for solvers to write PNG/PPM/SVG pictures of their state into
+    #[arg(long, global = true, value_name = "DIR")]
+    pub render: Option<PathBuf>,
+    /// Replay simulations frame by frame in the terminal, `--animate=FPS` sets the speed
+    #[arg(long, global = true, value_name = "FPS", num_args = 0..=1, require_equals = true, default_missing_value = "10")]
+    pub animate: Option<u32>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run a single day and exit
+    Run { year: String, day: String },
+    /// Encrypt input*.txt files into input*.txt.enc using the input key
+    Encrypt {
+        year: String,
+        /// Only this day, defaults to every day of the year
+        day: Option<String>,
+        /// Remove the plaintext files once encrypted
+        #[arg(long)]
+        delete: bool,
+    },
+    /// Decrypt input*.txt.enc files back into input*.txt using the input key
+    Decrypt {
+        year: String,
+        /// Only this day, defaults to every day of the year
+        day: Option<String>,
+        /// Remove the encrypted files once decrypted
+        #[arg(long)]
+        delete: bool,
+    },
+}
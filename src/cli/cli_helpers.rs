@@ -10,6 +10,19 @@ pub fn parse_args(year_str: &str, day_str: &str) -> Result<(u32, u32), String> {
     Ok((year, day))
 }
 
+pub fn parse_year_and_optional_day(
+    year_str: &str,
+    day_str: Option<String>,
+) -> Result<(u32, Option<u32>), String> {
+    let year = year_str
+        .parse()
+        .map_err(|_| Messages::INVALID_YEAR.to_string())?;
+    let day = day_str
+        .map(|d| d.parse().map_err(|_| Messages::INVALID_DAY.to_string()))
+        .transpose()?;
+    Ok((year, day))
+}
+
 pub fn read_input() -> Result<String, String> {
     let mut input = String::new();
     std::io::stdin()
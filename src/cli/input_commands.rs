@@ -0,0 +1,96 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    cli::messages::Messages,
+    solver::solver::{day_dir, year_dir},
+    utils::input_crypto::{ENCRYPTED_SUFFIX, InputKey},
+};
+
+pub fn encrypt_inputs(year: u32, day: Option<u32>, delete: bool) -> Result<(), String> {
+    let key = InputKey::load()?;
+    for path in input_files(year, day, ".txt")? {
+        let plaintext = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let encrypted_path = PathBuf::from(format!("{}{}", path.display(), ENCRYPTED_SUFFIX));
+        write_file(&encrypted_path, &key.encrypt(&plaintext))?;
+        if delete {
+            remove_file(&path)?;
+        }
+        println!("{} -> {}", path.display(), encrypted_path.display());
+    }
+    Ok(())
+}
+
+pub fn decrypt_inputs(year: u32, day: Option<u32>, delete: bool) -> Result<(), String> {
+    let key = InputKey::load()?;
+    for path in input_files(year, day, &format!(".txt{}", ENCRYPTED_SUFFIX))? {
+        let encoded = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let plaintext = key
+            .decrypt(&encoded)
+            .map_err(|e| format!("Failed to decrypt {}: {}", path.display(), e))?;
+        let plain_path = path.with_extension("");
+        write_file(&plain_path, &plaintext)?;
+        if delete {
+            remove_file(&path)?;
+        }
+        println!("{} -> {}", path.display(), plain_path.display());
+    }
+    Ok(())
+}
+
+fn input_files(year: u32, day: Option<u32>, suffix: &str) -> Result<Vec<PathBuf>, String> {
+    let day_dirs: Vec<PathBuf> = match day {
+        Some(day) => vec![PathBuf::from(day_dir(year, day))],
+        None => {
+            let mut dirs: Vec<PathBuf> = list_dir(Path::new(&year_dir(year)))?
+                .into_iter()
+                .filter(|path| {
+                    path.is_dir()
+                        && path
+                            .file_name()
+                            .and_then(|name| name.to_str())
+                            .is_some_and(|name| name.starts_with("day"))
+                })
+                .collect();
+            dirs.sort();
+            dirs
+        }
+    };
+
+    let mut files = Vec::new();
+    for dir in day_dirs {
+        let mut day_files: Vec<PathBuf> = list_dir(&dir)?
+            .into_iter()
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("input") && name.ends_with(suffix))
+            })
+            .collect();
+        day_files.sort();
+        files.extend(day_files);
+    }
+
+    if files.is_empty() {
+        return Err(Messages::NO_INPUT_FILES.to_string());
+    }
+    Ok(files)
+}
+
+fn list_dir(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))
+        .map(|entries| entries.filter_map(|e| e.ok().map(|e| e.path())).collect())
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn remove_file(path: &Path) -> Result<(), String> {
+    fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+}
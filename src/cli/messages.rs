@@ -9,5 +9,6 @@ impl Messages {
     pub const EXPECTED_FORMAT: &'static str = "Expected 'year day' format";
     pub const BOTH_REQUIRED: &'static str = "Both year and day must be provided";
     pub const TRY_AGAIN: &'static str = "Error, try again";
+    pub const NO_INPUT_FILES: &'static str = "No matching input files found";
     pub const REDO_MESSAGE: &'static str = "(press r to run current selection)";
 }
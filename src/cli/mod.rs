@@ -1,3 +1,5 @@
+pub mod args;
 pub mod cli_helpers;
+pub mod input_commands;
 pub mod messages;
 pub mod runner;
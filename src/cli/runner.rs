@@ -6,20 +6,12 @@ use crate::{
     years::run_day,
 };
 
+#[derive(Default)]
 pub struct Runner {
     pub current_year: Option<u32>,
     pub current_day: Option<u32>,
 }
 
-impl Default for Runner {
-    fn default() -> Self {
-        Self {
-            current_year: None,
-            current_day: None,
-        }
-    }
-}
-
 impl Runner {
     pub fn with_args(year_str: Option<String>, day_str: Option<String>) -> Result<Self, String> {
         match (year_str, day_str) {
@@ -35,6 +27,8 @@ impl Runner {
         }
     }
 
+    // keeps prompting until `quit`, a day whose input can't be loaded is reported and the
+    // selection kept so it can be rerun with `r` once the file is fixed
     pub fn read_user_input_and_execute(mut self) {
         loop {
             println!("{}", self.prompt());
@@ -53,7 +47,9 @@ impl Runner {
                 Ok((year, day)) => {
                     self.current_year = Some(year);
                     self.current_day = Some(day);
-                    run_day(year, day)
+                    if let Err(e) = run_day(year, day) {
+                        println!("{}: {}", Messages::TRY_AGAIN, e);
+                    }
                 }
                 Err(msg) => {
                     self.current_year = None;
@@ -1,3 +1,6 @@
+// lets code generated by aoc-derive name this crate as ::aoc_rust from inside it
+extern crate self as aoc_rust;
+
 pub mod cli;
 pub mod solver;
 pub mod toolbox;
@@ -1,23 +1,57 @@
-use std::env;
+use clap::Parser;
 
-use aoc_rust::{cli::runner::Runner, years::run_day};
+use aoc_rust::{
+    cli::{
+        args::{Cli, Command},
+        cli_helpers::{parse_args, parse_year_and_optional_day},
+        input_commands::{decrypt_inputs, encrypt_inputs},
+        runner::Runner,
+    },
+    solver::options,
+    years::run_day,
+};
 
 fn main() {
-    let mut args = env::args().skip(1);
-    let runner = match Runner::with_args(args.next(), args.next()) {
-        Ok(runner) => {
-            // If args were provided, run automatically
-            if let (Some(year), Some(day)) = (runner.current_year, runner.current_day) {
-                run_day(year, day);
-                println!();
-            }
-            runner
-        }
-        Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+    let cli = Cli::parse();
+    options::set_debug(cli.debug);
+    options::set_animate(cli.animate);
+    if let Some(dir) = cli.render {
+        options::set_render_dir(dir);
+    }
+
+    let result = match cli.command {
+        Some(Command::Run { year, day }) => parse_args(&year, &day)
+            .and_then(|(year, day)| run_day(year, day).map_err(|e| e.to_string())),
+        Some(Command::Encrypt { year, day, delete }) => parse_year_and_optional_day(&year, day)
+            .and_then(|(year, day)| encrypt_inputs(year, day, delete)),
+        Some(Command::Decrypt { year, day, delete }) => parse_year_and_optional_day(&year, day)
+            .and_then(|(year, day)| decrypt_inputs(year, day, delete)),
+        None => {
+            let runner = match Runner::with_args(cli.year, cli.day) {
+                Ok(runner) => {
+                    // If args were provided, run automatically
+                    if let (Some(year), Some(day)) = (runner.current_year, runner.current_day) {
+                        if let Err(e) = run_day(year, day) {
+                            eprintln!("Error: {}", e);
+                            std::process::exit(1);
+                        }
+                        println!();
+                    }
+                    runner
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            runner.read_user_input_and_execute();
+            Ok(())
         }
     };
 
-    runner.read_user_input_and_execute()
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
 }
@@ -1 +1,3 @@
+pub mod options;
+#[allow(clippy::module_inception)]
 pub mod solver;
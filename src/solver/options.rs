@@ -0,0 +1,45 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, AtomicU32, Ordering},
+    },
+};
+
+// run-wide switches set once from the command line, solvers only read them
+
+static DEBUG: AtomicBool = AtomicBool::new(false);
+static RENDER_DIR: OnceLock<PathBuf> = OnceLock::new();
+// frames per second, 0 while animation is off
+static ANIMATE_FPS: AtomicU32 = AtomicU32::new(0);
+
+pub fn set_debug(enabled: bool) {
+    DEBUG.store(enabled, Ordering::Relaxed);
+}
+
+// when set, solvers print intermediate state such as grid overlays to stderr
+pub fn debug() -> bool {
+    DEBUG.load(Ordering::Relaxed)
+}
+
+// only the first call sticks, the flag is read once at startup
+pub fn set_render_dir(dir: PathBuf) {
+    let _ = RENDER_DIR.set(dir);
+}
+
+// where solvers that draw pictures of their state write them, None unless --render was given
+pub fn render_dir() -> Option<&'static Path> {
+    RENDER_DIR.get().map(PathBuf::as_path)
+}
+
+pub fn set_animate(fps: Option<u32>) {
+    ANIMATE_FPS.store(fps.map_or(0, |fps| fps.max(1)), Ordering::Relaxed);
+}
+
+// the playback speed when solvers should record frames of their simulation
+pub fn animate() -> Option<u32> {
+    match ANIMATE_FPS.load(Ordering::Relaxed) {
+        0 => None,
+        fps => Some(fps),
+    }
+}
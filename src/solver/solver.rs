@@ -1,30 +1,44 @@
-use crate::utils::file_reader::FileReader;
+use crate::{
+    toolbox::animate::Recorder,
+    utils::{
+        file_reader::{FileReader, MemoryFileReader},
+        parse_error::InputError,
+    },
+};
+
+pub fn year_dir(year: u32) -> String {
+    format!("src/years/year{}", year)
+}
+
+pub fn day_dir(year: u32, day: u32) -> String {
+    format!("{}/day{:02}", year_dir(year), day)
+}
 
 pub trait Solver<Output>
 where
     Output: std::fmt::Display,
 {
     fn input_path(year: u32, day: u32) -> String {
-        format!("src/years/year{}/day{:02}/input.txt", year, day)
+        format!("{}/input.txt", day_dir(year, day))
     }
 
     fn test_input_path(year: u32, day: u32) -> String {
-        format!("src/years/year{}/day{:02}/input_test.txt", year, day)
+        format!("{}/input_test.txt", day_dir(year, day))
     }
 
     // in case the test input is different for part 2
     fn test_input_path_part_two(year: u32, day: u32) -> String {
-        format!("src/years/year{}/day{:02}/input_test_2.txt", year, day)
+        format!("{}/input_test_2.txt", day_dir(year, day))
     }
 
-    fn from_default_path<R: FileReader>(reader: &R, year: u32, day: u32) -> Result<Self, String>
+    fn from_default_path<R: FileReader>(reader: &R, year: u32, day: u32) -> Result<Self, InputError>
     where
         Self: Sized,
     {
         Self::new(reader, &Self::input_path(year, day))
     }
 
-    fn from_test_path<R: FileReader>(reader: &R, year: u32, day: u32) -> Result<Self, String>
+    fn from_test_path<R: FileReader>(reader: &R, year: u32, day: u32) -> Result<Self, InputError>
     where
         Self: Sized,
     {
@@ -35,20 +49,44 @@ where
         reader: &R,
         year: u32,
         day: u32,
-    ) -> Result<Self, String>
+    ) -> Result<Self, InputError>
     where
         Self: Sized,
     {
         Self::new(reader, &Self::test_input_path_part_two(year, day))
     }
 
-    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, String>
+    // builds the solver from an inline string, handy for small edge-case tests
+    fn from_str(input: &'static str) -> Result<Self, InputError>
+    where
+        Self: Sized,
+    {
+        let path = "inline";
+        Self::new(&MemoryFileReader::new().with_file(path, input), path)
+    }
+
+    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, InputError>
     where
         Self: Sized;
 
+    // loads the real input and solves it, input problems are returned instead of panicking
+    fn run<R: FileReader>(reader: &R, year: u32, day: u32) -> Result<(), InputError>
+    where
+        Self: Sized,
+    {
+        Self::from_default_path(reader, year, day)?.solve();
+        Ok(())
+    }
+
     fn part_one_solution(&mut self) -> Output;
     fn part_two_solution(&mut self) -> Output;
 
+    // frames recorded while solving, played by `solve` once the answers and timings are
+    // out so watching them isn't counted as solving time
+    fn take_frames(&mut self) -> Option<Recorder> {
+        None
+    }
+
     fn solve(&mut self) {
         use std::time::Instant;
 
@@ -63,5 +101,11 @@ where
         let part2_time = start.elapsed();
 
         println!("Part 2: {} (took {:?})", part2, part2_time);
+
+        if let Some(frames) = self.take_frames()
+            && let Err(err) = frames.play()
+        {
+            eprintln!("Failed to play the animation: {}", err);
+        }
     }
 }
@@ -0,0 +1,320 @@
+use std::{
+    fmt::Display,
+    fs,
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::Print,
+    terminal::{self, ClearType},
+};
+
+use crate::solver::options;
+
+const MAX_FPS: u32 = 240;
+
+// collects snapshots of a simulation for --animate, anything Display can be a frame so
+// grids, sparse grids and overlays all work, pushes are ignored when animation is off
+// so solvers can record unconditionally
+pub struct Recorder {
+    name: String,
+    frames: Vec<String>,
+    recording: bool,
+}
+
+impl Recorder {
+    // `name` prefixes the frame files when there's no terminal to play into
+    pub fn new(name: &str) -> Self {
+        Recorder {
+            name: name.to_string(),
+            frames: Vec::new(),
+            recording: options::animate().is_some(),
+        }
+    }
+
+    // for snapshots that are expensive to build, skip the work when nothing is kept
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    pub fn push(&mut self, frame: impl Display) {
+        if self.recording {
+            self.frames.push(frame.to_string());
+        }
+    }
+
+    pub fn frames(&self) -> &[String] {
+        &self.frames
+    }
+
+    // replays the frames in the terminal, or writes them out as numbered files when
+    // stdout isn't one, does nothing when animation is off
+    pub fn play(self) -> io::Result<()> {
+        let Some(fps) = options::animate() else {
+            return Ok(());
+        };
+        if self.frames.is_empty() {
+            return Ok(());
+        }
+        if io::stdout().is_terminal() {
+            play_in_terminal(&self.frames, fps)
+        } else {
+            let dir = options::render_dir().unwrap_or(Path::new("frames"));
+            let written = self.write_frames(dir)?;
+            eprintln!(
+                "Wrote {} frames of {} to {}",
+                written.len(),
+                self.name,
+                dir.display()
+            );
+            Ok(())
+        }
+    }
+
+    // `<name>-0001.txt` onwards in `dir`, returns the paths in frame order
+    pub fn write_frames(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+        let digits = self.frames.len().to_string().len().max(4);
+        self.frames
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                let path = dir.join(format!("{}-{:0digits$}.txt", self.name, i + 1));
+                fs::write(&path, frame).map(|_| path)
+            })
+            .collect()
+    }
+}
+
+// where playback is and what the keys have asked for, kept apart from the terminal so
+// the controls can be tested
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Playback {
+    frame: usize,
+    len: usize,
+    fps: u32,
+    paused: bool,
+    quit: bool,
+}
+
+impl Playback {
+    fn new(len: usize, fps: u32) -> Self {
+        Playback {
+            frame: 0,
+            len,
+            fps: fps.clamp(1, MAX_FPS),
+            paused: false,
+            quit: false,
+        }
+    }
+
+    fn delay(&self) -> Duration {
+        Duration::from_secs(1) / self.fps
+    }
+
+    // moves on a frame while playing, pausing on the last one so it can be looked at
+    fn tick(&mut self) {
+        if !self.paused {
+            self.frame += 1;
+            if self.frame + 1 >= self.len {
+                self.frame = self.len - 1;
+                self.paused = true;
+            }
+        }
+    }
+
+    // space plays and pauses, arrows step a frame at a time, +/- change speed,
+    // home/end jump, q, escape or ctrl-c stop
+    fn key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char(' ') => {
+                // playing again from the end starts over
+                if self.paused && self.frame + 1 == self.len {
+                    self.frame = 0;
+                }
+                self.paused = !self.paused;
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.paused = true;
+                self.frame = (self.frame + 1).min(self.len - 1);
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.paused = true;
+                self.frame = self.frame.saturating_sub(1);
+            }
+            KeyCode::Home => self.frame = 0,
+            KeyCode::End => self.frame = self.len - 1,
+            KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Up => {
+                self.fps = (self.fps * 2).min(MAX_FPS)
+            }
+            KeyCode::Char('-') | KeyCode::Down => self.fps = (self.fps / 2).max(1),
+            _ => {}
+        }
+    }
+
+    fn status(&self) -> String {
+        format!(
+            "frame {}/{}  {} fps{}  [space] play/pause  [<-/->] step  [+/-] speed  [q] quit",
+            self.frame + 1,
+            self.len,
+            self.fps,
+            if self.paused { "  paused" } else { "" },
+        )
+    }
+}
+
+// raw mode on an alternate screen with the cursor hidden, put back on drop so an error or
+// a panic mid playback doesn't leave the terminal unusable
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        // from here on dropping the guard undoes whatever part of the setup happened
+        let guard = TerminalGuard;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // nothing useful to do with a failure here, carry on restoring the rest
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn play_in_terminal(frames: &[String], fps: u32) -> io::Result<()> {
+    let _guard = TerminalGuard::enter()?;
+    run_playback(&mut io::stdout(), frames, fps)
+}
+
+fn run_playback(out: &mut impl Write, frames: &[String], fps: u32) -> io::Result<()> {
+    let mut playback = Playback::new(frames.len(), fps);
+    let mut shown = None;
+    let mut next_tick = Instant::now() + playback.delay();
+    while !playback.quit {
+        if shown != Some(playback.status()) {
+            draw(out, &frames[playback.frame], &playback.status())?;
+            shown = Some(playback.status());
+        }
+        let wait = next_tick.saturating_duration_since(Instant::now());
+        if event::poll(wait)? {
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                playback.key(key);
+            }
+        } else {
+            playback.tick();
+            next_tick = Instant::now() + playback.delay();
+        }
+    }
+    Ok(())
+}
+
+fn draw(out: &mut impl Write, frame: &str, status: &str) -> io::Result<()> {
+    queue!(out, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+    // raw mode doesn't turn \n into a carriage return as well
+    for line in frame.lines() {
+        queue!(out, Print(line), Print("\r\n"))?;
+    }
+    queue!(out, Print("\r\n"), Print(status))?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::toolbox::grid::Grid;
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_playback_pauses_on_the_last_frame() {
+        let mut playback = Playback::new(3, 10);
+        assert_eq!(playback.delay(), Duration::from_millis(100));
+        playback.tick();
+        assert_eq!((playback.frame, playback.paused), (1, false));
+        playback.tick();
+        assert_eq!((playback.frame, playback.paused), (2, true));
+        playback.tick();
+        assert_eq!(playback.frame, 2);
+        // playing from the end starts again
+        playback.key(press(KeyCode::Char(' ')));
+        assert_eq!((playback.frame, playback.paused), (0, false));
+    }
+
+    #[test]
+    fn test_playback_keys() {
+        let mut playback = Playback::new(5, 10);
+        playback.key(press(KeyCode::Right));
+        playback.key(press(KeyCode::Right));
+        assert_eq!((playback.frame, playback.paused), (2, true));
+        playback.tick();
+        assert_eq!(playback.frame, 2);
+        playback.key(press(KeyCode::Left));
+        assert_eq!(playback.frame, 1);
+        playback.key(press(KeyCode::End));
+        playback.key(press(KeyCode::Right));
+        assert_eq!(playback.frame, 4);
+
+        playback.key(press(KeyCode::Char('+')));
+        assert_eq!(playback.fps, 20);
+        for _ in 0..10 {
+            playback.key(press(KeyCode::Char('-')));
+        }
+        assert_eq!(playback.fps, 1);
+
+        assert!(!playback.quit);
+        playback.key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(playback.quit);
+    }
+
+    #[test]
+    fn test_draw_moves_to_the_top() {
+        let mut out = Vec::new();
+        draw(&mut out, "ab\ncd", "frame 1/1").unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("\x1b[1;1H\x1b[2J"));
+        assert!(text.ends_with("ab\r\ncd\r\n\r\nframe 1/1"));
+    }
+
+    #[test]
+    fn test_write_frames() {
+        let mut recorder = Recorder {
+            name: "blink".to_string(),
+            frames: Vec::new(),
+            recording: true,
+        };
+        let grid: Grid<char> = ".#.\n.#.".parse().unwrap();
+        recorder.push(&grid);
+        recorder.push(grid.transpose());
+        let dir = std::env::temp_dir().join(format!("aoc-frames-{}", std::process::id()));
+        let written = recorder.write_frames(&dir).unwrap();
+        assert_eq!(
+            written,
+            vec![dir.join("blink-0001.txt"), dir.join("blink-0002.txt")]
+        );
+        assert_eq!(fs::read_to_string(&written[1]).unwrap(), "..\n##\n..");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_recorder_off_keeps_nothing() {
+        let mut recorder = Recorder::new("off");
+        assert!(!recorder.is_recording());
+        recorder.push("frame");
+        assert!(recorder.frames().is_empty());
+    }
+}
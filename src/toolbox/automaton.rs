@@ -0,0 +1,502 @@
+use std::{
+    collections::{HashMap, HashSet, hash_map::DefaultHasher},
+    hash::{Hash, Hasher},
+    mem,
+};
+
+use crate::{
+    toolbox::{
+        grid::{
+            Grid, Point,
+            bits::BitGrid,
+            neighbors::{Edges, Neighborhood},
+            sparse::SparseGrid,
+        },
+        sparse_map::SparseMap,
+    },
+    utils::math_utils::Point2D,
+};
+
+// a board the automaton can run on, `step_into` writes the next generation into `next`
+// so the two buffers can be swapped instead of reallocated
+pub trait World: Clone + PartialEq {
+    type Cell: Clone + PartialEq;
+    // which cells count as next to a cell, square grids and hex maps have their own
+    type Neighborhood<'a>: Copy;
+
+    // returns how many cells changed
+    fn step_into(
+        &self,
+        next: &mut Self,
+        neighborhood: Self::Neighborhood<'_>,
+        edges: Edges,
+        rule: &impl Fn(&Self::Cell, &[&Self::Cell]) -> Self::Cell,
+    ) -> usize;
+
+    // equal worlds must give equal fingerprints, used to spot cycles, different worlds may
+    // collide so a match is only a hint
+    fn fingerprint(&self) -> u64
+    where
+        Self::Cell: Hash;
+}
+
+impl<T: Clone + PartialEq> World for Grid<T> {
+    type Cell = T;
+    type Neighborhood<'a> = Neighborhood<'a>;
+
+    fn step_into(
+        &self,
+        next: &mut Self,
+        neighborhood: Neighborhood,
+        edges: Edges,
+        rule: &impl Fn(&T, &[&T]) -> T,
+    ) -> usize {
+        // every cell gets overwritten, the buffer only needs the right shape
+        if (next.width(), next.height()) != (self.width(), self.height()) {
+            next.clone_from(self);
+        }
+        let mut around = Vec::new();
+        let mut changes = 0;
+        for (point, cell) in self.iter() {
+            around.clear();
+            around.extend(self.neighbors(point, neighborhood, edges).map(|p| &self[p]));
+            let new = rule(cell, &around);
+            changes += usize::from(new != *cell);
+            next[point] = new;
+        }
+        changes
+    }
+
+    fn fingerprint(&self) -> u64
+    where
+        T: Hash,
+    {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+// a bit per cell, the rule still sees one cell at a time but the generations stay packed
+impl World for BitGrid {
+    type Cell = bool;
+    type Neighborhood<'a> = Neighborhood<'a>;
+
+    fn step_into(
+        &self,
+        next: &mut Self,
+        neighborhood: Neighborhood,
+        edges: Edges,
+        rule: &impl Fn(&bool, &[&bool]) -> bool,
+    ) -> usize {
+        let (width, height) = (self.width(), self.height());
+        if (next.width(), next.height()) != (width, height) {
+            *next = BitGrid::new(width, height);
+        }
+        let mut around = Vec::new();
+        let mut changes = 0;
+        for point in (0..height).flat_map(|row| (0..width).map(move |col| Point::new(row, col))) {
+            let cell = self.get(point);
+            around.clear();
+            around.extend(
+                neighborhood
+                    .offsets()
+                    .iter()
+                    .filter_map(|&delta| edges.apply(point, delta, width, height))
+                    .map(|p| if self.get(p) { &true } else { &false }),
+            );
+            let new = rule(&cell, &around);
+            changes += usize::from(new != cell);
+            next.set(point, new);
+        }
+        changes
+    }
+
+    fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+// a world kept as a map of occupied cells on an unbounded plane, like SparseGrid or
+// HexMap, missing cells read as the default, implementing this gives a World for free
+pub trait SparseWorld: Clone + PartialEq {
+    type Key: Copy + Eq + Hash;
+    type Cell: Clone + PartialEq;
+    type Neighborhood<'a>: Copy;
+
+    fn cells(&self) -> impl Iterator<Item = (Self::Key, &Self::Cell)>;
+
+    fn cell(&self, key: Self::Key) -> &Self::Cell;
+
+    fn default_cell(&self) -> &Self::Cell;
+
+    fn neighbor_keys<'a>(
+        &self,
+        key: Self::Key,
+        neighborhood: Self::Neighborhood<'a>,
+    ) -> impl Iterator<Item = Self::Key>;
+
+    fn clear(&mut self);
+
+    fn insert(&mut self, key: Self::Key, cell: Self::Cell);
+}
+
+// only occupied cells and their neighbours can change, everything further out stays
+// the default, cells that become the default are dropped to keep the plane sparse
+impl<W: SparseWorld> World for W {
+    type Cell = W::Cell;
+    type Neighborhood<'a> = W::Neighborhood<'a>;
+
+    fn step_into(
+        &self,
+        next: &mut Self,
+        neighborhood: W::Neighborhood<'_>,
+        _edges: Edges,
+        rule: &impl Fn(&W::Cell, &[&W::Cell]) -> W::Cell,
+    ) -> usize {
+        next.clear();
+        let candidates: HashSet<_> = self
+            .cells()
+            .flat_map(|(key, _)| self.neighbor_keys(key, neighborhood).chain([key]))
+            .collect();
+        let mut around = Vec::new();
+        let mut changes = 0;
+        for key in candidates {
+            let cell = self.cell(key);
+            around.clear();
+            around.extend(self.neighbor_keys(key, neighborhood).map(|k| self.cell(k)));
+            let new = rule(cell, &around);
+            changes += usize::from(new != *cell);
+            if new != *self.default_cell() {
+                next.insert(key, new);
+            }
+        }
+        changes
+    }
+
+    fn fingerprint(&self) -> u64
+    where
+        W::Cell: Hash,
+    {
+        // the map iterates in any order, so combine the cells order independently, a sum
+        // rather than xor so equal hashes don't cancel out
+        self.cells().fold(0u64, |acc, cell| {
+            let mut hasher = DefaultHasher::new();
+            cell.hash(&mut hasher);
+            acc.wrapping_add(hasher.finish())
+        })
+    }
+}
+
+impl<T: Clone + PartialEq> SparseWorld for SparseGrid<T> {
+    type Key = Point2D;
+    type Cell = T;
+    type Neighborhood<'a> = Neighborhood<'a>;
+
+    fn cells(&self) -> impl Iterator<Item = (Point2D, &T)> {
+        self.iter()
+    }
+
+    fn cell(&self, key: Point2D) -> &T {
+        self.get(key)
+    }
+
+    fn default_cell(&self) -> &T {
+        SparseMap::default_cell(self)
+    }
+
+    fn neighbor_keys<'a>(
+        &self,
+        key: Point2D,
+        neighborhood: Self::Neighborhood<'a>,
+    ) -> impl Iterator<Item = Point2D> {
+        self.neighbors(key, neighborhood)
+    }
+
+    fn clear(&mut self) {
+        SparseGrid::clear(self);
+    }
+
+    fn insert(&mut self, key: Point2D, cell: T) {
+        self.set(key, cell);
+    }
+}
+
+// where `run_n` found the world repeating, generation `start` came back after `len` more
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+// applies `rule` to every cell at once, each cell sees the previous generation only
+pub struct Automaton<'a, W: World, R> {
+    world: W,
+    back: W,
+    rule: R,
+    neighborhood: W::Neighborhood<'a>,
+    edges: Edges,
+    generation: usize,
+}
+
+impl<'a, W, R> Automaton<'a, W, R>
+where
+    W: World,
+    R: Fn(&W::Cell, &[&W::Cell]) -> W::Cell,
+{
+    pub fn new(world: W, neighborhood: W::Neighborhood<'a>, rule: R) -> Self {
+        Automaton {
+            back: world.clone(),
+            world,
+            rule,
+            neighborhood,
+            edges: Edges::Bounded,
+            generation: 0,
+        }
+    }
+
+    // what happens at the border of a dense grid, sparse worlds have none
+    pub fn with_edges(mut self, edges: Edges) -> Self {
+        self.edges = edges;
+        self
+    }
+
+    pub fn world(&self) -> &W {
+        &self.world
+    }
+
+    pub fn into_world(self) -> W {
+        self.world
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    // one generation, returns how many cells changed
+    pub fn step(&mut self) -> usize {
+        let changes =
+            self.world
+                .step_into(&mut self.back, self.neighborhood, self.edges, &self.rule);
+        mem::swap(&mut self.world, &mut self.back);
+        self.generation += 1;
+        changes
+    }
+
+    // steps until nothing changes, returns the change count of every step that did
+    pub fn run_until_stable(&mut self) -> Vec<usize> {
+        let mut changes = Vec::new();
+        loop {
+            match self.step() {
+                0 => return changes,
+                changed => changes.push(changed),
+            }
+        }
+    }
+
+    // moves to generation `n`, once a world repeats the remaining full cycles are
+    // skipped rather than simulated, only each fingerprint's first generation is kept so
+    // a match is confirmed by replaying from the starting world and comparing, a
+    // collision can delay spotting the cycle but never reports a wrong one
+    pub fn run_n(&mut self, n: usize) -> Option<Cycle>
+    where
+        W::Cell: Hash,
+    {
+        let first = self.generation;
+        let start_world = self.world.clone();
+        let mut seen: HashMap<u64, usize> = HashMap::new();
+        while self.generation < n {
+            let generation = self.generation;
+            let start = *seen.entry(self.world.fingerprint()).or_insert(generation);
+            if start < generation && self.replay(&start_world, start - first) == self.world {
+                let cycle = Cycle {
+                    start,
+                    len: generation - start,
+                };
+                let remaining = (n - generation) % cycle.len;
+                self.generation = n - remaining;
+                for _ in 0..remaining {
+                    self.step();
+                }
+                return Some(cycle);
+            }
+            self.step();
+        }
+        None
+    }
+
+    // `world` after `steps` generations, leaving this automaton where it is
+    fn replay(&self, world: &W, steps: usize) -> W {
+        let mut world = world.clone();
+        let mut back = world.clone();
+        for _ in 0..steps {
+            world.step_into(&mut back, self.neighborhood, self.edges, &self.rule);
+            mem::swap(&mut world, &mut back);
+        }
+        world
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // conway's life on '#' and '.'
+    fn life(cell: &char, around: &[&char]) -> char {
+        match (cell, around.iter().filter(|&&&c| c == '#').count()) {
+            ('#', 2 | 3) | ('.', 3) => '#',
+            _ => '.',
+        }
+    }
+
+    fn grid(text: &str) -> Grid<char> {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_blinker_oscillates() {
+        let mut automaton = Automaton::new(
+            grid(".....\n..#..\n..#..\n..#..\n....."),
+            Neighborhood::Moore,
+            life,
+        );
+        assert_eq!(automaton.step(), 4);
+        assert_eq!(
+            automaton.world(),
+            &grid(".....\n.....\n.###.\n.....\n.....")
+        );
+        automaton.step();
+        assert_eq!(
+            automaton.world(),
+            &grid(".....\n..#..\n..#..\n..#..\n.....")
+        );
+        assert_eq!(automaton.generation(), 2);
+    }
+
+    #[test]
+    fn test_run_until_stable() {
+        // a block is a still life, the stray cell dies first
+        let start = grid("#....\n.....\n..##.\n..##.\n.....");
+        let mut automaton = Automaton::new(start, Neighborhood::Moore, life);
+        assert_eq!(automaton.run_until_stable(), vec![1]);
+        assert_eq!(automaton.generation(), 2);
+    }
+
+    #[test]
+    fn test_run_n_fast_forwards() {
+        let blinker = grid(".....\n..#..\n..#..\n..#..\n.....");
+        let mut automaton = Automaton::new(blinker.clone(), Neighborhood::Moore, life);
+        assert_eq!(
+            automaton.run_n(1_000_000_001),
+            Some(Cycle { start: 0, len: 2 })
+        );
+        assert_eq!(automaton.generation(), 1_000_000_001);
+        assert_ne!(automaton.world(), &blinker);
+        automaton.step();
+        assert_eq!(automaton.world(), &blinker);
+    }
+
+    // a grid whose fingerprints all collide
+    #[derive(Clone, PartialEq)]
+    struct Colliding(Grid<char>);
+
+    impl World for Colliding {
+        type Cell = char;
+        type Neighborhood<'a> = Neighborhood<'a>;
+
+        fn step_into(
+            &self,
+            next: &mut Self,
+            neighborhood: Neighborhood,
+            edges: Edges,
+            rule: &impl Fn(&char, &[&char]) -> char,
+        ) -> usize {
+            self.0.step_into(&mut next.0, neighborhood, edges, rule)
+        }
+
+        fn fingerprint(&self) -> u64 {
+            0
+        }
+    }
+
+    #[test]
+    fn test_run_n_checks_fingerprint_matches() {
+        // a glider on a 6x6 torus only comes back after 24 generations
+        let glider = grid(".#....\n..#...\n###...\n......\n......\n......");
+        let mut automaton = Automaton::new(Colliding(glider.clone()), Neighborhood::Moore, life)
+            .with_edges(Edges::Wrap);
+        assert_eq!(automaton.run_n(100), Some(Cycle { start: 0, len: 24 }));
+        let mut stepped = Automaton::new(glider, Neighborhood::Moore, life).with_edges(Edges::Wrap);
+        for _ in 0..100 {
+            stepped.step();
+        }
+        assert!(automaton.world().0 == *stepped.world());
+    }
+
+    #[test]
+    fn test_sparse_fingerprint_is_order_independent() {
+        let a: SparseGrid<u8> = [((0, 0), 1), ((5, -2), 1), ((3, 3), 2)]
+            .into_iter()
+            .collect();
+        let b: SparseGrid<u8> = [((3, 3), 2), ((0, 0), 1), ((5, -2), 1)]
+            .into_iter()
+            .collect();
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        let moved: SparseGrid<u8> = [((0, 0), 1), ((5, -2), 1), ((3, 4), 2)]
+            .into_iter()
+            .collect();
+        assert_ne!(a.fingerprint(), moved.fingerprint());
+    }
+
+    #[test]
+    fn test_wrapping_edges() {
+        // a glider on a 6x6 torus is back where it started after 24 generations
+        let glider = grid(".#....\n..#...\n###...\n......\n......\n......");
+        let mut automaton =
+            Automaton::new(glider.clone(), Neighborhood::Moore, life).with_edges(Edges::Wrap);
+        automaton.run_n(24);
+        assert_eq!(automaton.world(), &glider);
+    }
+
+    #[test]
+    fn test_sparse_glider_travels() {
+        let cells = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let mut glider = SparseGrid::new('.');
+        glider.extend(cells.map(|point| (point, '#')));
+        let mut automaton = Automaton::new(glider, Neighborhood::Moore, life);
+        automaton.run_n(4);
+        // dead cells aren't kept, and four generations move it one down and right
+        assert_eq!(automaton.world().len(), 5);
+        assert!(
+            cells
+                .iter()
+                .all(|&(x, y)| automaton.world().contains((x + 1, y + 1)))
+        );
+        assert_eq!(automaton.world().bounds(), Some(((1, 1), (3, 3))));
+    }
+
+    #[test]
+    fn test_bit_grid_matches_the_dense_grid() {
+        let glider = grid(".#....\n..#...\n###...\n......\n......\n......");
+        let bits = BitGrid::from_grid(&glider, |&c| c == '#');
+        let bit_life = |&cell: &bool, around: &[&bool]| {
+            matches!(
+                (cell, around.iter().filter(|&&&b| b).count()),
+                (true, 2 | 3) | (false, 3)
+            )
+        };
+        let mut dense = Automaton::new(glider, Neighborhood::Moore, life).with_edges(Edges::Wrap);
+        let mut packed =
+            Automaton::new(bits, Neighborhood::Moore, bit_life).with_edges(Edges::Wrap);
+        for _ in 0..6 {
+            assert_eq!(packed.step(), dense.step());
+            assert_eq!(
+                packed.world(),
+                &BitGrid::from_grid(dense.world(), |&c| c == '#')
+            );
+        }
+        assert_eq!(packed.run_n(100).map(|cycle| cycle.len), Some(24));
+    }
+}
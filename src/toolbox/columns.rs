@@ -0,0 +1,196 @@
+use std::ops::Range;
+
+// fixed-width text where blocks of cells are separated by columns that are blank on every line,
+// like the cephalopod math worksheet of 2025 day 6
+//
+// 123 328  51 64
+//  45 64  387 23
+//   6 98  215 314
+pub struct Worksheet {
+    blocks: Vec<Block>,
+}
+
+impl Worksheet {
+    pub fn parse<'a>(lines: impl IntoIterator<Item = &'a str>) -> Self {
+        let lines: Vec<Vec<char>> = lines.into_iter().map(|l| l.chars().collect()).collect();
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let is_separator = |col: usize| {
+            lines
+                .iter()
+                .all(|line| line.get(col).is_none_or(|c| *c == ' '))
+        };
+
+        let mut blocks = Vec::new();
+        let mut start = None;
+        for col in 0..=width {
+            match (start, col == width || is_separator(col)) {
+                (None, false) => start = Some(col),
+                (Some(from), true) => {
+                    blocks.push(Block::new(&lines, from..col));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        Worksheet { blocks }
+    }
+
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    // padded on the right
+    Left,
+    // padded on the left
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadOrder {
+    LeftToRight,
+    RightToLeft,
+}
+
+// one column block, every row is padded with spaces to the full block width
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    columns: Range<usize>,
+    rows: Vec<String>,
+}
+
+impl Block {
+    fn new(lines: &[Vec<char>], columns: Range<usize>) -> Self {
+        let rows = lines
+            .iter()
+            .map(|line| {
+                columns
+                    .clone()
+                    .map(|col| line.get(col).copied().unwrap_or(' '))
+                    .collect()
+            })
+            .collect();
+        Block { columns, rows }
+    }
+
+    // the character columns this block spans in the original text, handy for
+    // picking matching fields out of lines that weren't part of the worksheet
+    pub fn columns(&self) -> Range<usize> {
+        self.columns.clone()
+    }
+
+    pub fn width(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = Cell<'_>> {
+        self.rows.iter().map(|row| Cell { text: row })
+    }
+
+    // each row's value with the padding removed, top to bottom
+    pub fn read_rows(&self) -> Vec<&str> {
+        self.cells().map(|cell| cell.value()).collect()
+    }
+
+    // each column's characters read top to bottom with blanks skipped,
+    // so `123 / 45 / 6` read right-to-left gives 356, 24, 1
+    pub fn read_columns(&self, order: ReadOrder) -> Vec<String> {
+        let rows: Vec<Vec<char>> = self.rows.iter().map(|row| row.chars().collect()).collect();
+        let column = |col: usize| -> String {
+            rows.iter()
+                .map(|row| row[col])
+                .filter(|c| *c != ' ')
+                .collect()
+        };
+        match order {
+            ReadOrder::LeftToRight => (0..self.width()).map(column).collect(),
+            ReadOrder::RightToLeft => (0..self.width()).rev().map(column).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell<'a> {
+    text: &'a str,
+}
+
+impl<'a> Cell<'a> {
+    // the cell including its padding
+    pub fn raw(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn value(&self) -> &'a str {
+        self.text.trim()
+    }
+
+    // None when the value fills the whole block width or the cell is blank
+    pub fn align(&self) -> Option<Align> {
+        let value = self.value();
+        if value.is_empty() || value.len() == self.text.len() {
+            None
+        } else if self.text.starts_with(value) {
+            Some(Align::Left)
+        } else if self.text.ends_with(value) {
+            Some(Align::Right)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHEET: [&str; 3] = ["123 328  51 64 ", " 45 64  387 23 ", "  6 98  215 314"];
+
+    #[test]
+    fn test_splits_on_blank_columns() {
+        let sheet = Worksheet::parse(SHEET);
+        let ranges: Vec<_> = sheet.blocks().iter().map(|b| b.columns()).collect();
+        assert_eq!(ranges, vec![0..3, 4..7, 8..11, 12..15]);
+        assert_eq!(sheet.blocks()[0].read_rows(), vec!["123", "45", "6"]);
+    }
+
+    #[test]
+    fn test_alignment() {
+        let sheet = Worksheet::parse(SHEET);
+        let aligns: Vec<_> = sheet.blocks()[1].cells().map(|c| c.align()).collect();
+        assert_eq!(aligns, vec![None, Some(Align::Left), Some(Align::Left)]);
+        let aligns: Vec<_> = sheet.blocks()[0].cells().map(|c| c.align()).collect();
+        assert_eq!(aligns, vec![None, Some(Align::Right), Some(Align::Right)]);
+    }
+
+    #[test]
+    fn test_read_columns() {
+        let sheet = Worksheet::parse(SHEET);
+        assert_eq!(
+            sheet.blocks()[0].read_columns(ReadOrder::RightToLeft),
+            vec!["356", "24", "1"]
+        );
+        assert_eq!(
+            sheet.blocks()[3].read_columns(ReadOrder::LeftToRight),
+            vec!["623", "431", "4"]
+        );
+    }
+
+    #[test]
+    fn test_short_lines_are_padded() {
+        let sheet = Worksheet::parse(["12 3", "4"]);
+        assert_eq!(sheet.blocks().len(), 2);
+        assert_eq!(sheet.blocks()[1].read_rows(), vec!["3", ""]);
+        assert_eq!(sheet.blocks()[1].height(), 2);
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(Worksheet::parse([]).blocks().is_empty());
+    }
+}
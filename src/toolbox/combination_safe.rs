@@ -1,3 +1,35 @@
+use std::str::FromStr;
+
+use crate::utils::parse_error::OffsetError;
+
+// one line of the dial list like `L68`, left turns are negative
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dial(pub i16);
+
+impl FromStr for Dial {
+    type Err = OffsetError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let sign = match line.chars().next() {
+            Some('L') => -1,
+            Some('R') => 1,
+            _ => return Err(OffsetError::new(0, 1, "`L` or `R`")),
+        };
+        let amount = &line[1..];
+        if amount.is_empty() || !amount.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(OffsetError::new(
+                1,
+                amount.chars().count(),
+                "a number of clicks",
+            ));
+        }
+        let n: i16 = amount.parse().map_err(|_| {
+            OffsetError::new(1, amount.len(), "a number of clicks that fits in i16")
+        })?;
+        Ok(Dial(sign * n))
+    }
+}
+
 pub struct CombinationSafe {
     pub pos: i16,
 }
@@ -9,15 +41,10 @@ impl Default for CombinationSafe {
 }
 
 impl CombinationSafe {
-    pub fn process_dials(&mut self, dials: &[&str]) -> (i16, i16) {
+    pub fn process_dials(&mut self, dials: &[Dial]) -> (i16, i16) {
         dials
             .iter()
-            .map(|next| {
-                let (direction, amount) = next.split_at(1);
-                let n: i16 = amount.parse().unwrap();
-                let sign = if direction == "L" { -1 } else { 1 };
-                self.dial(sign * n)
-            })
+            .map(|&Dial(n)| self.dial(n))
             .fold((0, 0), |(land_acc, rot_acc), (land, rot)| {
                 (land_acc + land, rot_acc + rot)
             })
@@ -47,6 +74,25 @@ impl CombinationSafe {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_dial() {
+        assert_eq!("L68".parse(), Ok(Dial(-68)));
+        assert_eq!("R5".parse(), Ok(Dial(5)));
+        assert_eq!(
+            "".parse::<Dial>(),
+            Err(OffsetError::new(0, 1, "`L` or `R`"))
+        );
+        assert_eq!(
+            "é5".parse::<Dial>(),
+            Err(OffsetError::new(0, 1, "`L` or `R`"))
+        );
+        assert_eq!(
+            "L-5".parse::<Dial>(),
+            Err(OffsetError::new(1, 2, "a number of clicks"))
+        );
+        assert!("R99999".parse::<Dial>().is_err());
+    }
+
     #[test]
     fn test_dial_left() {
         let mut safe = CombinationSafe::default();
@@ -0,0 +1,452 @@
+use std::{fmt, str::FromStr};
+
+use crate::utils::parse_error::OffsetError;
+
+// arithmetic expressions with a swappable precedence table, for the "homework" style puzzles
+// where `+` and `*` bind equally or `+` binds tighter than `*`
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl Op {
+    pub fn from_char(c: char) -> Option<Op> {
+        match c {
+            '+' => Some(Op::Add),
+            '-' => Some(Op::Sub),
+            '*' => Some(Op::Mul),
+            '/' => Some(Op::Div),
+            '%' => Some(Op::Rem),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(&self) -> char {
+        match self {
+            Op::Add => '+',
+            Op::Sub => '-',
+            Op::Mul => '*',
+            Op::Div => '/',
+            Op::Rem => '%',
+        }
+    }
+
+    pub fn apply<T: Number>(&self, lhs: T, rhs: T) -> Result<T, String> {
+        let result = match self {
+            Op::Add => lhs.checked_add(rhs),
+            Op::Sub => lhs.checked_sub(rhs),
+            Op::Mul => lhs.checked_mul(rhs),
+            Op::Div => lhs.checked_div(rhs),
+            Op::Rem => lhs.checked_rem(rhs),
+        };
+        result.ok_or_else(|| format!("{} {} {} overflows or divides by zero", lhs, self, rhs))
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+// integer types the evaluator can use, every operation is checked so overflow is an error
+pub trait Number: Copy + FromStr + fmt::Display {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn checked_rem(self, rhs: Self) -> Option<Self>;
+    fn checked_neg(self) -> Option<Self>;
+}
+
+macro_rules! impl_number {
+    ($($t:ty),*) => {
+        $(impl Number for $t {
+            fn checked_add(self, rhs: Self) -> Option<Self> { <$t>::checked_add(self, rhs) }
+            fn checked_sub(self, rhs: Self) -> Option<Self> { <$t>::checked_sub(self, rhs) }
+            fn checked_mul(self, rhs: Self) -> Option<Self> { <$t>::checked_mul(self, rhs) }
+            fn checked_div(self, rhs: Self) -> Option<Self> { <$t>::checked_div(self, rhs) }
+            fn checked_rem(self, rhs: Self) -> Option<Self> { <$t>::checked_rem(self, rhs) }
+            fn checked_neg(self) -> Option<Self> { <$t>::checked_neg(self) }
+        })*
+    };
+}
+
+impl_number!(u64, i64, u128, i128);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+    Left,
+    Right,
+}
+
+// binding power per operator, higher binds tighter, operators left out are rejected when parsing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Precedence {
+    levels: Vec<(Op, u8, Assoc)>,
+}
+
+impl Precedence {
+    pub fn empty() -> Self {
+        Precedence { levels: Vec::new() }
+    }
+
+    pub fn with(mut self, op: Op, level: u8, assoc: Assoc) -> Self {
+        self.levels.retain(|(existing, _, _)| *existing != op);
+        self.levels.push((op, level, assoc));
+        self
+    }
+
+    // school maths, `*` `/` `%` over `+` `-`
+    pub fn standard() -> Self {
+        Self::empty()
+            .with(Op::Add, 1, Assoc::Left)
+            .with(Op::Sub, 1, Assoc::Left)
+            .with(Op::Mul, 2, Assoc::Left)
+            .with(Op::Div, 2, Assoc::Left)
+            .with(Op::Rem, 2, Assoc::Left)
+    }
+
+    // everything evaluates left to right
+    pub fn flat() -> Self {
+        [Op::Add, Op::Sub, Op::Mul, Op::Div, Op::Rem]
+            .into_iter()
+            .fold(Self::empty(), |table, op| table.with(op, 1, Assoc::Left))
+    }
+
+    // `+` binds tighter than `*`
+    pub fn addition_first() -> Self {
+        Self::empty()
+            .with(Op::Mul, 1, Assoc::Left)
+            .with(Op::Add, 2, Assoc::Left)
+    }
+
+    fn lookup(&self, op: Op) -> Option<(u8, Assoc)> {
+        self.levels
+            .iter()
+            .find(|(existing, _, _)| *existing == op)
+            .map(|&(_, level, assoc)| (level, assoc))
+    }
+}
+
+impl Default for Precedence {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr<T> {
+    Num(T),
+    Neg(Box<Expr<T>>),
+    Binary(Op, Box<Expr<T>>, Box<Expr<T>>),
+}
+
+impl<T: Number> Expr<T> {
+    pub fn parse(text: &str, precedence: &Precedence) -> Result<Self, OffsetError> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            end: text.len(),
+            precedence,
+        };
+        let expr = parser.expression(0)?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(token) => Err(OffsetError::new(token.offset, token.width, "an operator")),
+        }
+    }
+
+    // `a op b op c ...` as a left-deep tree, None when there are no operands
+    pub fn fold(op: Op, operands: impl IntoIterator<Item = T>) -> Option<Self> {
+        operands
+            .into_iter()
+            .map(Expr::Num)
+            .reduce(|lhs, rhs| Expr::Binary(op, Box::new(lhs), Box::new(rhs)))
+    }
+
+    pub fn eval(&self) -> Result<T, String> {
+        match self {
+            Expr::Num(n) => Ok(*n),
+            Expr::Neg(inner) => {
+                let value = inner.eval()?;
+                value
+                    .checked_neg()
+                    .ok_or_else(|| format!("-{} overflows", value))
+            }
+            Expr::Binary(op, lhs, rhs) => op.apply(lhs.eval()?, rhs.eval()?),
+        }
+    }
+
+    // the parse tree drawn one node per line
+    pub fn tree(&self) -> String {
+        let mut out = String::new();
+        self.write_tree(&mut out, "", "");
+        out
+    }
+
+    fn write_tree(&self, out: &mut String, first: &str, rest: &str) {
+        out.push_str(first);
+        match self {
+            Expr::Num(n) => out.push_str(&format!("{}\n", n)),
+            Expr::Neg(inner) => {
+                out.push_str("neg\n");
+                inner.write_tree(out, &format!("{}└── ", rest), &format!("{}    ", rest));
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                out.push_str(&format!("{}\n", op));
+                lhs.write_tree(out, &format!("{}├── ", rest), &format!("{}│   ", rest));
+                rhs.write_tree(out, &format!("{}└── ", rest), &format!("{}    ", rest));
+            }
+        }
+    }
+}
+
+// fully parenthesised so the precedence that was applied is visible
+impl<T: fmt::Display> fmt::Display for Expr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Num(n) => write!(f, "{}", n),
+            Expr::Neg(inner) => write!(f, "-{}", inner),
+            Expr::Binary(op, lhs, rhs) => write!(f, "({} {} {})", lhs, op, rhs),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind<'a> {
+    Num(&'a str),
+    Op(Op),
+    Open,
+    Close,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: TokenKind<'a>,
+    offset: usize,
+    width: usize,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token<'_>>, OffsetError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        let kind = match c {
+            c if c.is_whitespace() => continue,
+            '(' => TokenKind::Open,
+            ')' => TokenKind::Close,
+            c if c.is_ascii_digit() => {
+                let mut end = offset + 1;
+                while let Some(&(i, d)) = chars.peek()
+                    && d.is_ascii_digit()
+                {
+                    end = i + 1;
+                    chars.next();
+                }
+                TokenKind::Num(&text[offset..end])
+            }
+            c => match Op::from_char(c) {
+                Some(op) => TokenKind::Op(op),
+                None => {
+                    return Err(OffsetError::new(
+                        offset,
+                        c.len_utf8(),
+                        "a number or operator",
+                    ));
+                }
+            },
+        };
+        let width = match kind {
+            TokenKind::Num(digits) => digits.len(),
+            _ => 1,
+        };
+        tokens.push(Token {
+            kind,
+            offset,
+            width,
+        });
+    }
+    Ok(tokens)
+}
+
+// pratt parser, `min_level` is the weakest operator this call may consume, it's wider than
+// the table's levels so a left associative operator at level 255 can still ask for 256
+struct Parser<'t, 'a> {
+    tokens: &'t [Token<'a>],
+    pos: usize,
+    end: usize,
+    precedence: &'t Precedence,
+}
+
+impl<'t, 'a> Parser<'t, 'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self, expected: &str) -> Result<Token<'a>, OffsetError> {
+        let token = self
+            .peek()
+            .ok_or_else(|| OffsetError::new(self.end, 0, expected))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn expression<T: Number>(&mut self, min_level: u16) -> Result<Expr<T>, OffsetError> {
+        let mut lhs = self.operand()?;
+        while let Some(token) = self.peek() {
+            let TokenKind::Op(op) = token.kind else {
+                break;
+            };
+            let (level, assoc) = self.precedence.lookup(op).ok_or_else(|| {
+                OffsetError::new(
+                    token.offset,
+                    token.width,
+                    "an operator in the precedence table",
+                )
+            })?;
+            let level = u16::from(level);
+            if level < min_level {
+                break;
+            }
+            self.pos += 1;
+            let next_min = match assoc {
+                Assoc::Left => level + 1,
+                Assoc::Right => level,
+            };
+            let rhs = self.expression(next_min)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn operand<T: Number>(&mut self) -> Result<Expr<T>, OffsetError> {
+        let token = self.next("a number")?;
+        match token.kind {
+            TokenKind::Num(digits) => digits
+                .parse()
+                .map(Expr::Num)
+                .map_err(|_| OffsetError::new(token.offset, token.width, "a number that fits")),
+            TokenKind::Op(Op::Sub) => Ok(Expr::Neg(Box::new(self.operand()?))),
+            TokenKind::Open => {
+                let inner = self.expression(0)?;
+                let close = self.next("`)`")?;
+                if close.kind != TokenKind::Close {
+                    return Err(OffsetError::new(close.offset, close.width, "`)`"));
+                }
+                Ok(inner)
+            }
+            _ => Err(OffsetError::new(token.offset, token.width, "a number")),
+        }
+    }
+}
+
+// evaluates `text` under `precedence` in one go
+pub fn evaluate<T: Number>(text: &str, precedence: &Precedence) -> Result<T, String> {
+    Expr::<T>::parse(text, precedence)
+        .map_err(|e| e.to_string())?
+        .eval()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precedence_tables() {
+        let text = "1 + 2 * 3 + 4 * 5 + 6";
+        assert_eq!(evaluate::<u128>(text, &Precedence::standard()), Ok(33));
+        assert_eq!(evaluate::<u128>(text, &Precedence::flat()), Ok(71));
+        assert_eq!(
+            evaluate::<u128>(text, &Precedence::addition_first()),
+            Ok(231)
+        );
+    }
+
+    #[test]
+    fn test_parentheses() {
+        let text = "2 * 3 + (4 * 5)";
+        assert_eq!(evaluate::<u128>(text, &Precedence::flat()), Ok(26));
+        let text = "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2";
+        assert_eq!(evaluate::<u128>(text, &Precedence::flat()), Ok(13632));
+        assert_eq!(
+            evaluate::<u128>(text, &Precedence::addition_first()),
+            Ok(23340)
+        );
+    }
+
+    #[test]
+    fn test_associativity() {
+        let left = Precedence::standard();
+        let right = Precedence::standard().with(Op::Sub, 1, Assoc::Right);
+        assert_eq!(evaluate::<i128>("10 - 4 - 3", &left), Ok(3));
+        assert_eq!(evaluate::<i128>("10 - 4 - 3", &right), Ok(9));
+        assert_eq!(evaluate::<i128>("-(2 - 5) * -2", &left), Ok(-6));
+    }
+
+    #[test]
+    fn test_highest_level() {
+        let table =
+            Precedence::empty()
+                .with(Op::Add, 0, Assoc::Left)
+                .with(Op::Sub, 255, Assoc::Left);
+        assert_eq!(evaluate::<i128>("10 - 4 - 3 + 1", &table), Ok(4));
+    }
+
+    #[test]
+    fn test_checked_evaluation() {
+        let big = format!("{} * 2", u128::MAX);
+        assert!(evaluate::<u128>(&big, &Precedence::standard()).is_err());
+        assert!(evaluate::<u128>("1 / 0", &Precedence::standard()).is_err());
+        assert!(evaluate::<u128>("1 - 2", &Precedence::standard()).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let table = Precedence::standard();
+        assert_eq!(
+            Expr::<u128>::parse("1 + ", &table),
+            Err(OffsetError::new(4, 0, "a number"))
+        );
+        assert_eq!(
+            Expr::<u128>::parse("(1 + 2", &table),
+            Err(OffsetError::new(6, 0, "`)`"))
+        );
+        assert_eq!(
+            Expr::<u128>::parse("1 ^ 2", &table),
+            Err(OffsetError::new(2, 1, "a number or operator"))
+        );
+        assert_eq!(
+            Expr::<u128>::parse("1 - 2", &Precedence::addition_first()),
+            Err(OffsetError::new(
+                2,
+                1,
+                "an operator in the precedence table"
+            ))
+        );
+        assert_eq!(
+            Expr::<u128>::parse("1 2", &table),
+            Err(OffsetError::new(2, 1, "an operator"))
+        );
+    }
+
+    #[test]
+    fn test_fold_and_display() {
+        let expr = Expr::fold(Op::Mul, [123u128, 45, 6]).unwrap();
+        assert_eq!(expr.to_string(), "((123 * 45) * 6)");
+        assert_eq!(expr.eval(), Ok(33210));
+        assert_eq!(Expr::<u128>::fold(Op::Add, []), None);
+    }
+
+    #[test]
+    fn test_tree() {
+        let expr = Expr::<u128>::parse("(1 + 2) * 3", &Precedence::standard()).unwrap();
+        assert_eq!(expr.tree(), "*\n├── +\n│   ├── 1\n│   └── 2\n└── 3\n");
+    }
+}
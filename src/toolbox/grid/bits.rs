@@ -0,0 +1,486 @@
+use std::fmt;
+
+use crate::toolbox::grid::{Grid, Point, neighbors::Neighborhood};
+
+// a grid of booleans packed 64 to a word, every row starts on a fresh word so bit
+// `col % 64` of word `row * words_per_row + col / 64` is cell (row, col), bits past the
+// width are always clear so whole words can be counted and compared
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+// a few rows of a BitGrid cut out as a run of words, small shapes placed on a big grid
+// only touch the words they cover when tested and set
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitMask {
+    start: usize,
+    words: Vec<u64>,
+}
+
+impl BitMask {
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(64);
+        BitGrid {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Point) -> bool) -> Self {
+        let mut bits = BitGrid::new(width, height);
+        for row in 0..height {
+            for col in 0..width {
+                if f(Point::new(row, col)) {
+                    bits.insert(Point::new(row, col));
+                }
+            }
+        }
+        bits
+    }
+
+    // set wherever `predicate` holds for the grid's cell
+    pub fn from_grid<T>(grid: &Grid<T>, predicate: impl Fn(&T) -> bool) -> Self {
+        BitGrid::from_fn(grid.width(), grid.height(), |p| predicate(&grid[p]))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.row < self.height && point.col < self.width
+    }
+
+    // false off the grid
+    pub fn get(&self, point: Point) -> bool {
+        self.contains(point) && self.words[self.word(point)] >> (point.col % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, point: Point, value: bool) {
+        if value {
+            self.insert(point);
+        } else {
+            self.remove(point);
+        }
+    }
+
+    pub fn insert(&mut self, point: Point) {
+        assert!(self.contains(point), "{:?} is off the grid", point);
+        let word = self.word(point);
+        self.words[word] |= 1 << (point.col % 64);
+    }
+
+    pub fn remove(&mut self, point: Point) {
+        if self.contains(point) {
+            let word = self.word(point);
+            self.words[word] &= !(1 << (point.col % 64));
+        }
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    // set cells in row-major order
+    pub fn ones(&self) -> impl Iterator<Item = Point> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let row = i / self.words_per_row;
+            let base = (i % self.words_per_row) * 64;
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                (rest != 0).then(|| {
+                    let bit = rest.trailing_zeros() as usize;
+                    rest &= rest - 1;
+                    Point::new(row, base + bit)
+                })
+            })
+        })
+    }
+
+    pub fn to_grid<T>(&self, on: T, off: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.width, self.height, |p| {
+            if self.get(p) { on.clone() } else { off.clone() }
+        })
+    }
+
+    // any cell set in both, the grids must be the same size
+    pub fn intersects(&self, other: &BitGrid) -> bool {
+        self.check_size(other);
+        self.words.iter().zip(&other.words).any(|(a, b)| a & b != 0)
+    }
+
+    // sets every cell set in `other`
+    pub fn union_with(&mut self, other: &BitGrid) {
+        self.check_size(other);
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(a, b)| *a |= b);
+    }
+
+    // keeps only the cells also set in `other`
+    pub fn intersect_with(&mut self, other: &BitGrid) {
+        self.check_size(other);
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(a, b)| *a &= b);
+    }
+
+    // clears every cell set in `other`
+    pub fn difference_with(&mut self, other: &BitGrid) {
+        self.check_size(other);
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(a, b)| *a &= !b);
+    }
+
+    // the rows holding any set cell as a mask for this grid's size
+    pub fn to_mask(&self) -> BitMask {
+        let first = self.words.iter().position(|&w| w != 0);
+        let last = self.words.iter().rposition(|&w| w != 0);
+        match (first, last) {
+            (Some(first), Some(last)) => BitMask {
+                start: first,
+                words: self.words[first..=last].to_vec(),
+            },
+            _ => BitMask {
+                start: 0,
+                words: Vec::new(),
+            },
+        }
+    }
+
+    // a mask for a grid this size with just `points` set, without building the grid
+    pub fn mask_of(&self, points: impl IntoIterator<Item = Point>) -> BitMask {
+        let mut set: Vec<(usize, u64)> = points
+            .into_iter()
+            .map(|point| {
+                assert!(self.contains(point), "{:?} is off the grid", point);
+                (self.word(point), 1 << (point.col % 64))
+            })
+            .collect();
+        set.sort_unstable();
+        let Some((&(start, _), &(end, _))) = set.first().zip(set.last()) else {
+            return BitMask {
+                start: 0,
+                words: Vec::new(),
+            };
+        };
+        let mut words = vec![0; end - start + 1];
+        for (word, bit) in set {
+            words[word - start] |= bit;
+        }
+        BitMask { start, words }
+    }
+
+    // the mask has to come from a grid the same size as this one
+    pub fn overlaps(&self, mask: &BitMask) -> bool {
+        self.words[mask.start..mask.start + mask.words.len()]
+            .iter()
+            .zip(&mask.words)
+            .any(|(a, b)| a & b != 0)
+    }
+
+    pub fn set_mask(&mut self, mask: &BitMask) {
+        self.words[mask.start..mask.start + mask.words.len()]
+            .iter_mut()
+            .zip(&mask.words)
+            .for_each(|(a, b)| *a |= b);
+    }
+
+    pub fn clear_mask(&mut self, mask: &BitMask) {
+        self.words[mask.start..mask.start + mask.words.len()]
+            .iter_mut()
+            .zip(&mask.words)
+            .for_each(|(a, b)| *a &= !b);
+    }
+
+    // cell p of the result is cell p + (d_row, d_col) of this grid, clear where that
+    // falls off the edge, so shifting by a neighbour offset lines every cell up with
+    // that neighbour
+    pub fn shifted(&self, (d_row, d_col): (isize, isize)) -> BitGrid {
+        let mut out = BitGrid::new(self.width, self.height);
+        let (whole, bits) = (d_col.unsigned_abs() / 64, d_col.unsigned_abs() % 64);
+        let n = self.words_per_row;
+        for row in 0..self.height {
+            let Some(from) = row
+                .checked_add_signed(d_row)
+                .filter(|&from| from < self.height)
+            else {
+                continue;
+            };
+            let src = &self.words[from * n..(from + 1) * n];
+            let word = |i: isize| {
+                usize::try_from(i)
+                    .ok()
+                    .and_then(|i| src.get(i).copied())
+                    .unwrap_or(0)
+            };
+            for (i, dest) in out.words[row * n..(row + 1) * n].iter_mut().enumerate() {
+                let i = i as isize;
+                *dest = if d_col >= 0 {
+                    let at = i + whole as isize;
+                    let high = if bits == 0 {
+                        0
+                    } else {
+                        word(at + 1) << (64 - bits)
+                    };
+                    word(at) >> bits | high
+                } else {
+                    let at = i - whole as isize;
+                    let low = if bits == 0 {
+                        0
+                    } else {
+                        word(at - 1) >> (64 - bits)
+                    };
+                    word(at) << bits | low
+                };
+            }
+        }
+        out.clear_tails();
+        out
+    }
+
+    // how many neighbours of every cell are set, added up a whole word at a time
+    pub fn neighbor_counts(&self, neighborhood: Neighborhood) -> NeighborCounts {
+        // planes[i] holds bit i of every cell's count
+        let mut planes: Vec<Vec<u64>> = Vec::new();
+        for &offset in neighborhood.offsets() {
+            let shifted = self.shifted(offset);
+            for (i, &word) in shifted.words.iter().enumerate() {
+                let mut carry = word;
+                for plane in planes.iter_mut() {
+                    if carry == 0 {
+                        break;
+                    }
+                    (plane[i], carry) = (plane[i] ^ carry, plane[i] & carry);
+                }
+                if carry != 0 {
+                    let mut plane = vec![0; self.words.len()];
+                    plane[i] = carry;
+                    planes.push(plane);
+                }
+            }
+        }
+        NeighborCounts {
+            shape: BitGrid::new(self.width, self.height),
+            planes,
+        }
+    }
+
+    fn word(&self, point: Point) -> usize {
+        point.row * self.words_per_row + point.col / 64
+    }
+
+    fn check_size(&self, other: &BitGrid) {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "bit grids differ in size"
+        );
+    }
+
+    // clears the bits past the width in the last word of each row
+    fn clear_tails(&mut self) {
+        if !self.width.is_multiple_of(64) {
+            let keep = (1u64 << (self.width % 64)) - 1;
+            for row in 0..self.height {
+                self.words[(row + 1) * self.words_per_row - 1] &= keep;
+            }
+        }
+    }
+}
+
+// the result of BitGrid::neighbor_counts, kept bit-sliced so comparisons against a
+// count stay word parallel too
+pub struct NeighborCounts {
+    // an all clear grid of the right size to build results from
+    shape: BitGrid,
+    planes: Vec<Vec<u64>>,
+}
+
+impl NeighborCounts {
+    pub fn get(&self, point: Point) -> usize {
+        if !self.shape.contains(point) {
+            return 0;
+        }
+        let (word, bit) = (self.shape.word(point), point.col % 64);
+        self.planes
+            .iter()
+            .enumerate()
+            .map(|(i, plane)| ((plane[word] >> bit & 1) as usize) << i)
+            .sum()
+    }
+
+    // cells with fewer than `count` neighbours set
+    pub fn fewer_than(&self, count: usize) -> BitGrid {
+        self.compare(count).0
+    }
+
+    // cells with exactly `count` neighbours set
+    pub fn exactly(&self, count: usize) -> BitGrid {
+        self.compare(count).1
+    }
+
+    // (less, equal) masks, walking the count's bits from the top down
+    fn compare(&self, count: usize) -> (BitGrid, BitGrid) {
+        let mut less = self.shape.clone();
+        let mut equal = self.shape.clone();
+        equal.words.fill(!0);
+        let top = self
+            .planes
+            .len()
+            .max((usize::BITS - count.leading_zeros()) as usize);
+        for i in (0..top).rev() {
+            let count_bit = i < usize::BITS as usize && count >> i & 1 == 1;
+            for (w, (lt, eq)) in less
+                .words
+                .iter_mut()
+                .zip(equal.words.iter_mut())
+                .enumerate()
+            {
+                let plane = self.planes.get(i).map_or(0, |plane| plane[w]);
+                if count_bit {
+                    *lt |= *eq & !plane;
+                    *eq &= plane;
+                } else {
+                    *eq &= !plane;
+                }
+            }
+        }
+        less.clear_tails();
+        equal.clear_tails();
+        (less, equal)
+    }
+}
+
+impl fmt::Display for BitGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_grid('#', '.'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn bits(text: &str) -> BitGrid {
+        let grid: Grid<char> = text.parse().unwrap();
+        BitGrid::from_grid(&grid, |&c| c == '#')
+    }
+
+    #[test]
+    fn test_set_get_and_count() {
+        let mut grid = BitGrid::new(70, 3);
+        grid.insert(Point::new(0, 0));
+        grid.insert(Point::new(1, 69));
+        grid.insert(Point::new(2, 64));
+        assert!(grid.get(Point::new(1, 69)));
+        assert!(!grid.get(Point::new(1, 68)));
+        assert!(!grid.get(Point::new(1, 70)));
+        assert_eq!(grid.count_ones(), 3);
+        assert_eq!(
+            grid.ones().collect::<Vec<_>>(),
+            vec![Point::new(0, 0), Point::new(1, 69), Point::new(2, 64)]
+        );
+        grid.remove(Point::new(1, 69));
+        grid.set(Point::new(2, 64), false);
+        assert_eq!(grid.count_ones(), 1);
+    }
+
+    #[test]
+    fn test_masks() {
+        let shape = bits("....\n.##.\n..#.\n....");
+        let mask = shape.to_mask();
+        assert_eq!(mask.count_ones(), 3);
+        let points = [Point::new(1, 1), Point::new(1, 2), Point::new(2, 2)];
+        assert_eq!(shape.mask_of(points), mask);
+        let mut board = bits("....\n....\n.#..\n....");
+        assert!(!board.overlaps(&mask));
+        board.set_mask(&mask);
+        assert_eq!(board, bits("....\n.##.\n.##.\n...."));
+        assert!(board.overlaps(&mask));
+        board.clear_mask(&mask);
+        assert_eq!(board, bits("....\n....\n.#..\n...."));
+    }
+
+    #[test]
+    fn test_shifted_lines_up_neighbours() {
+        let grid = bits("#..\n.#.\n..#");
+        // each cell takes the value of the cell to its right
+        assert_eq!(grid.shifted((0, 1)), bits("...\n#..\n.#."));
+        assert_eq!(grid.shifted((-1, -1)), bits("...\n.#.\n..#"));
+    }
+
+    #[test]
+    fn test_neighbor_counts() {
+        let grid = bits("###\n#.#\n###");
+        let counts = grid.neighbor_counts(Neighborhood::Moore);
+        assert_eq!(counts.get(Point::new(1, 1)), 8);
+        assert_eq!(counts.get(Point::new(0, 0)), 2);
+        assert_eq!(counts.get(Point::new(0, 1)), 4);
+        assert_eq!(counts.exactly(8), bits("...\n.#.\n..."));
+        assert_eq!(counts.fewer_than(3), bits("#.#\n...\n#.#"));
+        assert_eq!(counts.fewer_than(9).count_ones(), 9);
+        assert_eq!(counts.fewer_than(100).count_ones(), 9);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_counts_match_grid_neighbors(
+            cells in prop::collection::vec(any::<bool>(), 3 * 130),
+            count in 0usize..9,
+        ) {
+            // wide enough that shifts cross word boundaries
+            let grid = Grid::from_vec(130, 3, cells).unwrap();
+            let bits = BitGrid::from_grid(&grid, |&b| b);
+            let counts = bits.neighbor_counts(Neighborhood::Moore);
+            for point in grid.points() {
+                let naive = grid.surrounding(point).filter(|&p| grid[p]).count();
+                prop_assert_eq!(counts.get(point), naive);
+                prop_assert_eq!(counts.fewer_than(count).get(point), naive < count);
+            }
+        }
+
+        #[test]
+        fn prop_shift_matches_offset(
+            cells in prop::collection::vec(any::<bool>(), 2 * 150),
+            d_row in -2isize..=2,
+            d_col in -140isize..=140,
+        ) {
+            let grid = Grid::from_vec(150, 2, cells).unwrap();
+            let shifted = BitGrid::from_grid(&grid, |&b| b).shifted((d_row, d_col));
+            for point in grid.points() {
+                let there = point.offset((d_row, d_col)).and_then(|p| grid.get(p)).copied();
+                prop_assert_eq!(shifted.get(point), there.unwrap_or(false));
+            }
+        }
+    }
+}
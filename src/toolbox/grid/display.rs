@@ -0,0 +1,190 @@
+use std::{collections::HashMap, fmt};
+
+use crate::toolbox::grid::{Grid, Point};
+
+impl<T: Copy + Into<char>> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for &cell in row {
+                write!(f, "{}", cell.into())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    fn ansi(&self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Mark {
+    symbol: Option<char>,
+    color: Option<Color>,
+}
+
+// draws paths, highlighted cells and labels over a grid without touching it,
+// later layers win where they overlap, e.g.
+// eprintln!("{}", grid.overlay().highlight(&hits, Color::Red).label(start, 'S'));
+pub struct Overlay<'a, T> {
+    grid: &'a Grid<T>,
+    base: Box<dyn Fn(&T) -> char + 'a>,
+    marks: HashMap<Point, Mark>,
+    colored: bool,
+}
+
+impl<T> Grid<T> {
+    pub fn overlay(&self) -> Overlay<'_, T>
+    where
+        T: Copy + Into<char>,
+    {
+        self.overlay_with(|&cell| cell.into())
+    }
+
+    // for cell types that aren't chars already, `base` picks the character for each cell
+    pub fn overlay_with<'a>(&'a self, base: impl Fn(&T) -> char + 'a) -> Overlay<'a, T> {
+        Overlay {
+            grid: self,
+            base: Box::new(base),
+            marks: HashMap::new(),
+            colored: true,
+        }
+    }
+}
+
+impl<T> Overlay<'_, T> {
+    // ANSI colours are on by default, turn them off when writing to a file
+    pub fn colored(mut self, colored: bool) -> Self {
+        self.colored = colored;
+        self
+    }
+
+    // replaces the character of every cell on the path
+    pub fn path<'p>(mut self, points: impl IntoIterator<Item = &'p Point>, symbol: char) -> Self {
+        for point in points {
+            self.marks.entry(*point).or_default().symbol = Some(symbol);
+        }
+        self
+    }
+
+    // colours cells but keeps their character
+    pub fn highlight<'p>(
+        mut self,
+        points: impl IntoIterator<Item = &'p Point>,
+        color: Color,
+    ) -> Self {
+        for point in points {
+            self.marks.entry(*point).or_default().color = Some(color);
+        }
+        self
+    }
+
+    pub fn label(mut self, point: Point, symbol: char) -> Self {
+        self.marks.entry(point).or_default().symbol = Some(symbol);
+        self
+    }
+
+    pub fn colored_label(mut self, point: Point, symbol: char, color: Color) -> Self {
+        self.marks.insert(
+            point,
+            Mark {
+                symbol: Some(symbol),
+                color: Some(color),
+            },
+        );
+        self
+    }
+}
+
+impl<T> fmt::Display for Overlay<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.grid.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for (col, cell) in row.iter().enumerate() {
+                let mark = self
+                    .marks
+                    .get(&Point::new(i, col))
+                    .copied()
+                    .unwrap_or_default();
+                let symbol = mark.symbol.unwrap_or_else(|| (self.base)(cell));
+                match mark.color.filter(|_| self.colored) {
+                    Some(color) => write!(f, "\x1b[{}m{}\x1b[0m", color.ansi(), symbol)?,
+                    None => write!(f, "{}", symbol)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        "..^\n.^.".parse().unwrap()
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(grid().to_string(), "..^\n.^.");
+        let digits = Grid::from_digits("12\n34").unwrap().map(|d| b'0' + d);
+        assert_eq!(digits.to_string(), "12\n34");
+    }
+
+    #[test]
+    fn test_overlay_layers() {
+        let grid = grid();
+        let path = [Point::new(0, 0), Point::new(1, 0)];
+        let rendered = grid
+            .overlay()
+            .colored(false)
+            .path(&path, '|')
+            .label(Point::new(0, 0), 'S')
+            .to_string();
+        assert_eq!(rendered, "S.^\n|^.");
+    }
+
+    #[test]
+    fn test_highlight_colors() {
+        let grid = grid();
+        let hits = [Point::new(0, 2)];
+        let rendered = grid.overlay().highlight(&hits, Color::Red).to_string();
+        assert_eq!(rendered, "..\x1b[31m^\x1b[0m\n.^.");
+        let plain = grid.overlay().highlight(&hits, Color::Red).colored(false);
+        assert_eq!(plain.to_string(), "..^\n.^.");
+    }
+
+    #[test]
+    fn test_overlay_with() {
+        let walls = Grid::parse_with("#.\n.#", |c| c == '#').unwrap();
+        let rendered = walls
+            .overlay_with(|&wall| if wall { '█' } else { ' ' })
+            .to_string();
+        assert_eq!(rendered, "█ \n █");
+    }
+}
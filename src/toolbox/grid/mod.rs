@@ -0,0 +1,488 @@
+use std::ops::{Index, IndexMut};
+
+pub mod bits;
+pub mod display;
+pub mod neighbors;
+pub mod parse;
+pub mod path;
+pub mod pattern;
+pub mod region;
+pub mod sparse;
+pub mod transform;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    LEFT,
+    RIGHT,
+    UP,
+    DOWN,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    pub fn offset(&self, row_width: usize) -> i32 {
+        match self {
+            Direction::LEFT => -1,
+            Direction::RIGHT => 1,
+            Direction::UP => -(row_width as i32),
+            Direction::DOWN => row_width as i32,
+            Direction::UpLeft => -(row_width as i32) - 1,
+            Direction::UpRight => -(row_width as i32) + 1,
+            Direction::DownLeft => row_width as i32 - 1,
+            Direction::DownRight => row_width as i32 + 1,
+        }
+    }
+
+    pub fn all() -> [Direction; 8] {
+        [
+            Direction::UP,
+            Direction::DOWN,
+            Direction::LEFT,
+            Direction::RIGHT,
+            Direction::UpLeft,
+            Direction::UpRight,
+            Direction::DownLeft,
+            Direction::DownRight,
+        ]
+    }
+
+    // the four that pathfinding usually wants, clockwise from up
+    pub fn cardinal() -> [Direction; 4] {
+        [
+            Direction::UP,
+            Direction::RIGHT,
+            Direction::DOWN,
+            Direction::LEFT,
+        ]
+    }
+
+    // (row, col) step, rows grow downwards
+    pub fn delta(&self) -> (isize, isize) {
+        match self {
+            Direction::LEFT => (0, -1),
+            Direction::RIGHT => (0, 1),
+            Direction::UP => (-1, 0),
+            Direction::DOWN => (1, 0),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (-1, 1),
+            Direction::DownLeft => (1, -1),
+            Direction::DownRight => (1, 1),
+        }
+    }
+
+    // quarter turns, diagonals turn too so UpRight goes to UpLeft on the left
+    pub fn turn_left(&self) -> Direction {
+        match self {
+            Direction::UP => Direction::LEFT,
+            Direction::LEFT => Direction::DOWN,
+            Direction::DOWN => Direction::RIGHT,
+            Direction::RIGHT => Direction::UP,
+            Direction::UpRight => Direction::UpLeft,
+            Direction::UpLeft => Direction::DownLeft,
+            Direction::DownLeft => Direction::DownRight,
+            Direction::DownRight => Direction::UpRight,
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        self.opposite().turn_left()
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::UP => Direction::DOWN,
+            Direction::DOWN => Direction::UP,
+            Direction::LEFT => Direction::RIGHT,
+            Direction::RIGHT => Direction::LEFT,
+            Direction::UpLeft => Direction::DownRight,
+            Direction::DownRight => Direction::UpLeft,
+            Direction::UpRight => Direction::DownLeft,
+            Direction::DownLeft => Direction::UpRight,
+        }
+    }
+}
+
+// a cell position, rows grow downwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Point {
+    pub fn new(row: usize, col: usize) -> Self {
+        Point { row, col }
+    }
+
+    // None when the step would leave the non-negative quadrant
+    pub fn offset(&self, (d_row, d_col): (isize, isize)) -> Option<Point> {
+        Some(Point::new(
+            self.row.checked_add_signed(d_row)?,
+            self.col.checked_add_signed(d_col)?,
+        ))
+    }
+
+    pub fn step(&self, direction: Direction) -> Option<Point> {
+        self.offset(direction.delta())
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((row, col): (usize, usize)) -> Self {
+        Point { row, col }
+    }
+}
+
+// row-major cells in one flat Vec, cell (row, col) lives at row * width + col
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height: if width == 0 { 0 } else { height },
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Point) -> T) -> Self {
+        let cells = (0..width * height)
+            .map(|i| f(Point::new(i / width, i % width)))
+            .collect();
+        Grid {
+            width,
+            height: if width == 0 { 0 } else { height },
+            cells,
+        }
+    }
+
+    // None when `cells` doesn't hold exactly width * height values, like every constructor
+    // a grid with no columns has no rows either
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Option<Self> {
+        (cells.len() == width * height).then_some(Grid {
+            width,
+            height: if width == 0 { 0 } else { height },
+            cells,
+        })
+    }
+
+    // every row must be as long as the first, Err holds the index of the first row that isn't
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, usize> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if let Some(bad) = rows.iter().position(|row| row.len() != width) {
+            return Err(bad);
+        }
+        let height = if width == 0 { 0 } else { rows.len() };
+        Ok(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, point: impl Into<Point>) -> bool {
+        let point = point.into();
+        point.row < self.height && point.col < self.width
+    }
+
+    pub fn index_of(&self, point: impl Into<Point>) -> Option<usize> {
+        let point = point.into();
+        self.contains(point)
+            .then_some(point.row * self.width + point.col)
+    }
+
+    pub fn point_of(&self, flat_index: usize) -> Point {
+        Point::new(flat_index / self.width, flat_index % self.width)
+    }
+
+    pub fn get(&self, point: impl Into<Point>) -> Option<&T> {
+        self.index_of(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: impl Into<Point>) -> Option<&mut T> {
+        self.index_of(point).map(|i| &mut self.cells[i])
+    }
+
+    pub fn set(&mut self, point: impl Into<Point>, value: T) -> Option<()> {
+        *self.get_mut(point)? = value;
+        Some(())
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    pub fn into_cells(self) -> Vec<T> {
+        self.cells
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (self.point_of(i), cell))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> + use<T> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| Point::new(i / width, i % width))
+    }
+
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|i| self.point_of(i))
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.height).then(|| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        // chunks_exact panics on a zero chunk size, an empty grid has no rows anyway
+        self.cells.chunks_exact(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        let width = self.width;
+        let cells = if col < width { &self.cells[..] } else { &[] };
+        cells.iter().skip(col).step_by(width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    // steps by row and column rather than adding a flat offset, so moves can't wrap
+    // around a row edge onto the neighbouring row
+    pub fn try_offset_index(&self, flat_index: usize, direction: &Direction) -> Option<usize> {
+        if flat_index >= self.cells.len() {
+            return None;
+        }
+        let there = self.point_of(flat_index).step(*direction)?;
+        self.index_of(there)
+    }
+
+    pub fn flat(&self) -> Vec<&T> {
+        self.cells.iter().collect()
+    }
+
+    pub fn get_all_neighbor_indices(&self, flat_index: usize) -> Vec<usize> {
+        Direction::all()
+            .iter()
+            .filter_map(|dir| self.try_offset_index(flat_index, dir)) // dir, not offset
+            .collect()
+    }
+
+    pub fn get_mut_by_index(&mut self, flat_index: usize) -> Option<&mut T> {
+        self.cells.get_mut(flat_index)
+    }
+
+    pub fn set_by_index(&mut self, flat_index: usize, value: T) -> Option<()> {
+        *self.cells.get_mut(flat_index)? = value;
+        Some(())
+    }
+
+    pub fn get_by_index(&self, flat_index: usize) -> Option<&T> {
+        self.cells.get(flat_index)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        let i = self.index_of(point).unwrap_or_else(|| {
+            panic!(
+                "{:?} is outside a {}x{} grid",
+                point, self.width, self.height
+            )
+        });
+        &self.cells[i]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{:?} is outside a {}x{} grid", point, width, height))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self[Point::new(row, col)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        &mut self[Point::new(row, col)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::toolbox::grid::transform::Rect;
+
+    fn numbered(width: usize, height: usize) -> Grid<usize> {
+        Grid::from_fn(width, height, |p| p.row * 100 + p.col)
+    }
+
+    #[test]
+    fn test_non_square_dimensions() {
+        let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 6);
+        assert_eq!(grid[Point::new(0, 1)], 2);
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.row(1), Some(&[4, 5, 6][..]));
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![3, 6]);
+    }
+
+    #[test]
+    fn test_ragged_rows_are_rejected() {
+        assert_eq!(Grid::from_rows(vec![vec![1, 2], vec![3]]), Err(1));
+        assert!(Grid::from_vec(2, 2, vec![1, 2, 3]).is_none());
+    }
+
+    #[test]
+    fn test_offset_uses_width_not_height() {
+        // 4 wide, 2 tall: the cell below index 1 is index 5
+        let grid = numbered(4, 2);
+        assert_eq!(grid.try_offset_index(1, &Direction::DOWN), Some(5));
+        assert_eq!(grid.try_offset_index(3, &Direction::RIGHT), None);
+        assert_eq!(grid.try_offset_index(4, &Direction::LEFT), None);
+        assert_eq!(grid.get_all_neighbor_indices(0), vec![4, 1, 5]);
+    }
+
+    #[test]
+    fn test_empty_grid() {
+        let grid: Grid<u8> = Grid::from_rows(vec![]).unwrap();
+        assert!(grid.is_empty());
+        assert_eq!(grid.rows().count(), 0);
+        assert_eq!(grid.columns().count(), 0);
+    }
+
+    #[test]
+    fn test_zero_width_grids_have_no_rows() {
+        let grids: [Grid<u8>; 4] = [
+            Grid::new(0, 5, 0),
+            Grid::from_fn(0, 5, |_| 0),
+            Grid::from_vec(0, 5, vec![]).unwrap(),
+            Grid::from_rows(vec![vec![]; 5]).unwrap(),
+        ];
+        for grid in grids {
+            assert_eq!((grid.width(), grid.height()), (0, 0));
+            assert_eq!(grid.rows().count(), 0);
+            assert_eq!(grid.transpose(), grid);
+        }
+
+        // no rows of three columns turns into three columns of nothing, which is empty too
+        let flat: Grid<u8> = Grid::new(3, 0, 0);
+        let empty = Grid::new(0, 3, 0);
+        for turned in [flat.transpose(), flat.rotate_cw(), flat.rotate_ccw()] {
+            assert_eq!((turned.width(), turned.height()), (0, 0));
+            assert_eq!(turned, empty);
+        }
+        let grid = Grid::new(3, 2, 0u8);
+        let slit = grid.subgrid(Rect::new(Point::new(0, 1), 0, 2)).unwrap();
+        assert_eq!((slit.width(), slit.height()), (0, 0));
+        assert_eq!(slit.rows().count(), 0);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_index_round_trips(width in 1usize..30, height in 1usize..30) {
+            let grid = numbered(width, height);
+            prop_assert_eq!(grid.len(), width * height);
+            for (point, &value) in grid.iter() {
+                prop_assert_eq!(value, point.row * 100 + point.col);
+                let i = grid.index_of(point).unwrap();
+                prop_assert_eq!(grid.point_of(i), point);
+                prop_assert_eq!(grid.get_by_index(i), Some(&value));
+            }
+            prop_assert!(grid.get((height, 0)).is_none());
+            prop_assert!(grid.get((0, width)).is_none());
+        }
+
+        #[test]
+        fn prop_rows_and_columns(width in 1usize..30, height in 1usize..30) {
+            let grid = numbered(width, height);
+            prop_assert_eq!(grid.rows().count(), height);
+            prop_assert!(grid.rows().all(|row| row.len() == width));
+            prop_assert_eq!(grid.columns().count(), width);
+            for (col, column) in grid.columns().enumerate() {
+                let column: Vec<usize> = column.copied().collect();
+                let expected: Vec<usize> = (0..height).map(|row| row * 100 + col).collect();
+                prop_assert_eq!(column, expected);
+            }
+        }
+
+        #[test]
+        fn prop_neighbors_are_adjacent(width in 1usize..30, height in 1usize..30, seed in any::<usize>()) {
+            let grid = numbered(width, height);
+            let i = seed % grid.len();
+            let here = grid.point_of(i);
+            let neighbors = grid.get_all_neighbor_indices(i);
+            for n in &neighbors {
+                let there = grid.point_of(*n);
+                prop_assert!(here.row.abs_diff(there.row) <= 1);
+                prop_assert!(here.col.abs_diff(there.col) <= 1);
+                prop_assert_ne!(here, there);
+            }
+            let expected = (0..grid.len())
+                .map(|j| grid.point_of(j))
+                .filter(|p| p != &here && p.row.abs_diff(here.row) <= 1 && p.col.abs_diff(here.col) <= 1)
+                .count();
+            prop_assert_eq!(neighbors.len(), expected);
+        }
+    }
+}
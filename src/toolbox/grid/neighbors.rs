@@ -0,0 +1,218 @@
+use crate::toolbox::grid::{Grid, Point};
+
+const VON_NEUMANN: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+// same order as Direction::all
+const MOORE: [(isize, isize); 8] = [
+    (-1, 0),
+    (1, 0),
+    (0, -1),
+    (0, 1),
+    (-1, -1),
+    (-1, 1),
+    (1, -1),
+    (1, 1),
+];
+
+// which cells count as next to a cell, as (row, col) offsets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood<'a> {
+    // up, right, down, left
+    VonNeumann,
+    // all 8 surrounding cells
+    Moore,
+    // any offsets, e.g. knight moves
+    Custom(&'a [(isize, isize)]),
+}
+
+impl<'a> Neighborhood<'a> {
+    pub fn offsets(&self) -> &'a [(isize, isize)] {
+        match self {
+            Neighborhood::VonNeumann => &VON_NEUMANN,
+            Neighborhood::Moore => &MOORE,
+            Neighborhood::Custom(offsets) => offsets,
+        }
+    }
+}
+
+// what happens to a step that leaves the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Edges {
+    // the neighbour doesn't exist
+    #[default]
+    Bounded,
+    // comes back in on the opposite side, like a torus
+    Wrap,
+    // bounces back off the edge, so one step left from column 0 lands on column 1
+    Reflect,
+}
+
+impl Edges {
+    // moves `point` by `delta` in a width x height grid
+    pub fn apply(
+        &self,
+        point: Point,
+        (d_row, d_col): (isize, isize),
+        width: usize,
+        height: usize,
+    ) -> Option<Point> {
+        Some(Point::new(
+            self.axis(point.row, d_row, height)?,
+            self.axis(point.col, d_col, width)?,
+        ))
+    }
+
+    fn axis(&self, at: usize, delta: isize, len: usize) -> Option<usize> {
+        if len == 0 {
+            return None;
+        }
+        let moved = at as isize + delta;
+        match self {
+            Edges::Bounded => (0..len as isize).contains(&moved).then_some(moved as usize),
+            Edges::Wrap => Some(moved.rem_euclid(len as isize) as usize),
+            Edges::Reflect => {
+                // bouncing between both walls repeats every 2 * (len - 1) steps
+                let period = 2 * (len as isize - 1);
+                if period == 0 {
+                    return Some(0);
+                }
+                let folded = moved.rem_euclid(period);
+                Some(folded.min(period - folded) as usize)
+            }
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    // positions next to `point` in the order of the neighbourhood's offsets, with
+    // Wrap and Reflect a small grid can yield the same cell more than once
+    pub fn neighbors<'a>(
+        &self,
+        point: Point,
+        neighborhood: Neighborhood<'a>,
+        edges: Edges,
+    ) -> impl Iterator<Item = Point> + use<'a, T> {
+        let (width, height) = (self.width, self.height);
+        neighborhood
+            .offsets()
+            .iter()
+            .filter_map(move |&delta| edges.apply(point, delta, width, height))
+    }
+
+    // the up to 4 orthogonal neighbours inside the grid
+    pub fn adjacent(&self, point: Point) -> impl Iterator<Item = Point> + use<T> {
+        self.neighbors(point, Neighborhood::VonNeumann, Edges::Bounded)
+    }
+
+    // the up to 8 neighbours inside the grid, diagonals included
+    pub fn surrounding(&self, point: Point) -> impl Iterator<Item = Point> + use<T> {
+        self.neighbors(point, Neighborhood::Moore, Edges::Bounded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::toolbox::grid::Direction;
+
+    fn grid() -> Grid<u8> {
+        Grid::new(4, 3, 0)
+    }
+
+    fn points(pairs: &[(usize, usize)]) -> Vec<Point> {
+        pairs.iter().map(|&pair| pair.into()).collect()
+    }
+
+    #[test]
+    fn test_bounded() {
+        let grid = grid();
+        let corner: Vec<Point> = grid.adjacent(Point::new(0, 0)).collect();
+        assert_eq!(corner, points(&[(0, 1), (1, 0)]));
+        assert_eq!(grid.surrounding(Point::new(1, 1)).count(), 8);
+        assert_eq!(grid.surrounding(Point::new(2, 3)).count(), 3);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let grid = grid();
+        let corner: Vec<Point> = grid
+            .neighbors(Point::new(0, 0), Neighborhood::VonNeumann, Edges::Wrap)
+            .collect();
+        assert_eq!(corner, points(&[(2, 0), (0, 1), (1, 0), (0, 3)]));
+    }
+
+    #[test]
+    fn test_reflect() {
+        let grid = grid();
+        let corner: Vec<Point> = grid
+            .neighbors(Point::new(0, 3), Neighborhood::VonNeumann, Edges::Reflect)
+            .collect();
+        assert_eq!(corner, points(&[(1, 3), (0, 2), (1, 3), (0, 2)]));
+    }
+
+    #[test]
+    fn test_custom() {
+        let knight = [
+            (-2, -1),
+            (-2, 1),
+            (-1, 2),
+            (1, 2),
+            (2, 1),
+            (2, -1),
+            (1, -2),
+            (-1, -2),
+        ];
+        let grid = grid();
+        let moves: Vec<Point> = grid
+            .neighbors(
+                Point::new(0, 0),
+                Neighborhood::Custom(&knight),
+                Edges::Bounded,
+            )
+            .collect();
+        assert_eq!(moves, points(&[(1, 2), (2, 1)]));
+    }
+
+    #[test]
+    fn test_direction_turns() {
+        for direction in Direction::all() {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.opposite().opposite(), direction);
+            assert_eq!(direction.turn_left().turn_left(), direction.opposite());
+            let (d_row, d_col) = direction.delta();
+            assert_eq!(direction.opposite().delta(), (-d_row, -d_col));
+        }
+        assert_eq!(Direction::UP.turn_right(), Direction::RIGHT);
+        assert_eq!(Direction::UpRight.turn_right(), Direction::DownRight);
+        assert_eq!(Point::new(0, 0).step(Direction::LEFT), None);
+        assert_eq!(
+            Point::new(0, 0).step(Direction::DownRight),
+            Some(Point::new(1, 1))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_wrap_and_reflect_stay_inside(
+            width in 1usize..10,
+            height in 1usize..10,
+            row in 0usize..10,
+            col in 0usize..10,
+            d_row in -25isize..25,
+            d_col in -25isize..25,
+        ) {
+            let point = Point::new(row % height, col % width);
+            for edges in [Edges::Wrap, Edges::Reflect] {
+                let moved = edges.apply(point, (d_row, d_col), width, height).unwrap();
+                prop_assert!(moved.row < height && moved.col < width);
+            }
+            let bounded = Edges::Bounded.apply(point, (d_row, d_col), width, height);
+            let inside = (point.row as isize + d_row, point.col as isize + d_col);
+            prop_assert_eq!(
+                bounded.is_some(),
+                (0..height as isize).contains(&inside.0) && (0..width as isize).contains(&inside.1)
+            );
+        }
+    }
+}
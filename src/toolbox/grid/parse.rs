@@ -0,0 +1,150 @@
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{
+    toolbox::grid::{Grid, Point},
+    utils::parse_error::OffsetError,
+};
+
+// where the marker characters of a map were found, like the `S` and `E` of a maze
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Markers {
+    positions: HashMap<char, Vec<Point>>,
+}
+
+impl Markers {
+    // every occurrence in reading order
+    pub fn all(&self, marker: char) -> &[Point] {
+        self.positions.get(&marker).map_or(&[], |points| points)
+    }
+
+    // the first occurrence, for markers that should appear once
+    pub fn one(&self, marker: char) -> Option<Point> {
+        self.all(marker).first().copied()
+    }
+}
+
+impl<T> Grid<T> {
+    // one cell per character, every line must be as wide as the first
+    pub fn parse_with(text: &str, mut f: impl FnMut(char) -> T) -> Result<Self, OffsetError> {
+        Self::try_parse_with(text, "", |c| Some(f(c)))
+    }
+
+    // like parse_with but also records where each of `markers` appears, the marker
+    // cells themselves still go through `f`
+    pub fn parse_with_markers(
+        text: &str,
+        markers: &str,
+        mut f: impl FnMut(char) -> T,
+    ) -> Result<(Self, Markers), OffsetError> {
+        let mut seen = Vec::new();
+        let mut index = 0;
+        let grid = Self::try_parse_with(text, "", |c| {
+            if markers.contains(c) {
+                seen.push((c, index));
+            }
+            index += 1;
+            Some(f(c))
+        })?;
+        let mut found = Markers::default();
+        for (marker, index) in seen {
+            found
+                .positions
+                .entry(marker)
+                .or_default()
+                .push(grid.point_of(index));
+        }
+        Ok((grid, found))
+    }
+
+    // `f` returning None fails the parse at that character with `expected`
+    fn try_parse_with(
+        text: &str,
+        expected: &str,
+        mut f: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, OffsetError> {
+        let mut rows = Vec::new();
+        // (byte offset of the line, its width in chars)
+        let mut widths = Vec::new();
+        for line in text.lines() {
+            let line_start = line.as_ptr() as usize - text.as_ptr() as usize;
+            let row = line
+                .char_indices()
+                .map(|(i, c)| f(c).ok_or_else(|| OffsetError::new(line_start + i, 1, expected)))
+                .collect::<Result<Vec<T>, _>>()?;
+            widths.push((line_start, row.len()));
+            rows.push(row);
+        }
+        Grid::from_rows(rows).map_err(|bad| {
+            let (start, width) = widths[bad];
+            OffsetError::new(start, width, format!("a row of width {}", widths[0].1))
+        })
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = OffsetError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Grid::parse_with(text, |c| c)
+    }
+}
+
+impl Grid<u8> {
+    // height maps and the like, `0`-`9` become 0-9
+    pub fn from_digits(text: &str) -> Result<Self, OffsetError> {
+        Grid::try_parse_with(text, "a digit", |c| c.to_digit(10).map(|digit| digit as u8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        let grid: Grid<char> = "#.#\n.#.\n".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 1)], '#');
+    }
+
+    #[test]
+    fn test_parse_with() {
+        let grid = Grid::parse_with("@.\n.@", |c| c == '@').unwrap();
+        assert_eq!(grid.cells(), &[true, false, false, true]);
+    }
+
+    #[test]
+    fn test_from_digits() {
+        let grid = Grid::from_digits("0123\n4567").unwrap();
+        assert_eq!(grid[(1, 3)], 7);
+        assert_eq!(
+            Grid::from_digits("01\n2x"),
+            Err(OffsetError::new(4, 1, "a digit"))
+        );
+    }
+
+    #[test]
+    fn test_ragged_row() {
+        assert_eq!(
+            "###\n##".parse::<Grid<char>>(),
+            Err(OffsetError::new(4, 2, "a row of width 3"))
+        );
+        // widths count chars, not bytes
+        assert_eq!(
+            "éé\néé\néèê".parse::<Grid<char>>(),
+            Err(OffsetError::new(10, 3, "a row of width 2"))
+        );
+        assert!("éé\n..".parse::<Grid<char>>().is_ok());
+    }
+
+    #[test]
+    fn test_markers() {
+        let (grid, markers) =
+            Grid::parse_with_markers("S..\n.#.\n..E\n.E.", "SE", |c| c == '#').unwrap();
+        assert_eq!(markers.one('S'), Some(Point::new(0, 0)));
+        assert_eq!(markers.all('E'), &[Point::new(2, 2), Point::new(3, 1)]);
+        assert_eq!(markers.one('X'), None);
+        assert!(grid[(1, 1)]);
+        assert!(!grid[(0, 0)]);
+    }
+}
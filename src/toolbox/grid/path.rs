@@ -0,0 +1,252 @@
+use std::collections::VecDeque;
+
+use crate::toolbox::{
+    grid::{Direction, Grid, Point},
+    pathfinding::{self, Path},
+};
+
+// a position plus the way it's facing, for mazes where turning costs something
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pose {
+    pub point: Point,
+    pub facing: Direction,
+}
+
+impl Pose {
+    pub fn new(point: impl Into<Point>, facing: Direction) -> Self {
+        Pose {
+            point: point.into(),
+            facing,
+        }
+    }
+}
+
+// all searches here move orthogonally and only onto cells `passable` accepts, the
+// start cell itself is never checked
+impl<T> Grid<T> {
+    // steps from `start` to every cell, None where it can't be reached
+    pub fn bfs_distances(
+        &self,
+        start: Point,
+        passable: impl Fn(&T) -> bool,
+    ) -> Grid<Option<usize>> {
+        let mut distances = Grid::new(self.width, self.height, None);
+        if !self.contains(start) {
+            return distances;
+        }
+        distances[start] = Some(0);
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some((point, steps)) = queue.pop_front() {
+            for next in self.adjacent(point) {
+                if distances[next].is_none() && passable(&self[next]) {
+                    distances[next] = Some(steps + 1);
+                    queue.push_back((next, steps + 1));
+                }
+            }
+        }
+        distances
+    }
+
+    // fewest steps from `start` to `goal`, every step costing 1
+    pub fn shortest_path(
+        &self,
+        start: Point,
+        goal: Point,
+        passable: impl Fn(&T) -> bool,
+    ) -> Option<Path<Point>> {
+        if !self.contains(start) {
+            return None;
+        }
+        let mut came_from: Grid<Option<Point>> = Grid::new(self.width, self.height, None);
+        let mut queue = VecDeque::from([start]);
+        while let Some(point) = queue.pop_front() {
+            if point == goal {
+                let mut steps = vec![goal];
+                while let Some(&prev) = steps.last().and_then(|&at| came_from[at].as_ref()) {
+                    steps.push(prev);
+                }
+                steps.reverse();
+                return Some(Path {
+                    cost: steps.len() as u64 - 1,
+                    steps,
+                });
+            }
+            for next in self.adjacent(point) {
+                if next != start && came_from[next].is_none() && passable(&self[next]) {
+                    came_from[next] = Some(point);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    // cheapest path where `cost(from, to)` prices each step
+    pub fn dijkstra(
+        &self,
+        start: Point,
+        goal: Point,
+        passable: impl Fn(&T) -> bool,
+        cost: impl Fn(Point, Point) -> u64,
+    ) -> Option<Path<Point>> {
+        pathfinding::dijkstra(
+            start,
+            |&point| self.passable_steps(point, &passable, &cost),
+            |&point| point == goal,
+        )
+    }
+
+    // like dijkstra but heads for the goal first, every step must cost at least 1 so the
+    // manhattan distance stays a lower bound, debug builds check that as costs are read
+    pub fn astar(
+        &self,
+        start: Point,
+        goal: Point,
+        passable: impl Fn(&T) -> bool,
+        cost: impl Fn(Point, Point) -> u64,
+    ) -> Option<Path<Point>> {
+        let cost = |from, to| {
+            let step = cost(from, to);
+            debug_assert!(step >= 1, "astar step costs must be at least 1");
+            step
+        };
+        pathfinding::astar(
+            start,
+            |&point| self.passable_steps(point, &passable, &cost),
+            |point| (point.row.abs_diff(goal.row) + point.col.abs_diff(goal.col)) as u64,
+            |&point| point == goal,
+        )
+    }
+
+    // dijkstra over positions with a facing, each move steps to an orthogonal neighbour
+    // and faces the way it moved, `cost(from, to)` sees both poses so it can charge for
+    // turning or return None to forbid the move, e.g. reversing
+    pub fn dijkstra_facing(
+        &self,
+        start: Pose,
+        goal: Point,
+        passable: impl Fn(&T) -> bool,
+        cost: impl Fn(Pose, Pose) -> Option<u64>,
+    ) -> Option<Path<Pose>> {
+        pathfinding::dijkstra(
+            start,
+            |&from| {
+                Direction::cardinal()
+                    .into_iter()
+                    .filter_map(|facing| {
+                        let to = Pose::new(from.point.step(facing)?, facing);
+                        let open = passable(self.get(to.point)?);
+                        open.then(|| cost(from, to)).flatten().map(|c| (to, c))
+                    })
+                    .collect::<Vec<_>>()
+            },
+            |pose| pose.point == goal,
+        )
+    }
+
+    fn passable_steps(
+        &self,
+        point: Point,
+        passable: &impl Fn(&T) -> bool,
+        cost: &impl Fn(Point, Point) -> u64,
+    ) -> Vec<(Point, u64)> {
+        self.adjacent(point)
+            .filter(|&next| passable(&self[next]))
+            .map(|next| (next, cost(point, next)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "\
+S..#....
+.#.#.##.
+.#...#..
+.####.#.
+......#E";
+
+    fn maze() -> (Grid<char>, Point, Point) {
+        let grid: Grid<char> = MAZE.parse().unwrap();
+        let start = grid.position(|&c| c == 'S').unwrap();
+        let goal = grid.position(|&c| c == 'E').unwrap();
+        (grid, start, goal)
+    }
+
+    fn open(c: &char) -> bool {
+        *c != '#'
+    }
+
+    fn is_connected(path: &[Point]) -> bool {
+        path.windows(2)
+            .all(|w| w[0].row.abs_diff(w[1].row) + w[0].col.abs_diff(w[1].col) == 1)
+    }
+
+    #[test]
+    fn test_bfs_distances() {
+        let (grid, start, goal) = maze();
+        let distances = grid.bfs_distances(start, open);
+        assert_eq!(distances[start], Some(0));
+        assert_eq!(distances[(0, 2)], Some(2));
+        assert_eq!(distances[(0, 3)], None);
+        assert_eq!(distances[goal], Some(15));
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let (grid, start, goal) = maze();
+        let path = grid.shortest_path(start, goal, open).unwrap();
+        assert_eq!(path.len(), 15);
+        assert_eq!(path.steps.first(), Some(&start));
+        assert_eq!(path.steps.last(), Some(&goal));
+        assert!(is_connected(&path.steps));
+        assert!(path.steps.iter().all(|&p| open(&grid[p])));
+        assert_eq!(grid.shortest_path(start, Point::new(0, 3), open), None);
+    }
+
+    #[test]
+    fn test_weighted_searches_agree() {
+        let (grid, start, goal) = maze();
+        // walking along row 4 is three times as slow
+        let cost = |_: Point, to: Point| if to.row == 4 { 3 } else { 1 };
+        let slow = grid.dijkstra(start, goal, open, cost).unwrap();
+        let guided = grid.astar(start, goal, open, cost).unwrap();
+        assert_eq!(slow.cost, guided.cost);
+        assert!(is_connected(&guided.steps));
+        assert_eq!(slow.cost, 14 + 3);
+        assert!(slow.steps.iter().all(|p| p.row != 4 || p.col == 7));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "at least 1")]
+    fn test_astar_rejects_free_steps() {
+        let (grid, start, goal) = maze();
+        grid.astar(start, goal, open, |_, _| 0);
+    }
+
+    #[test]
+    fn test_turn_costs() {
+        // two routes of equal length, the top one turns once and the bottom one twice
+        let grid: Grid<char> = "....\n.##.\n....".parse().unwrap();
+        let start = Pose::new((2, 0), Direction::UP);
+        let cost = |from: Pose, to: Pose| {
+            if to.facing == from.facing.opposite() {
+                None
+            } else if to.facing == from.facing {
+                Some(1)
+            } else {
+                Some(1001)
+            }
+        };
+        let path = grid
+            .dijkstra_facing(start, Point::new(0, 3), open, cost)
+            .unwrap();
+        assert_eq!(path.cost, 4 + 1001);
+        let points: Vec<Point> = path.steps.iter().map(|pose| pose.point).collect();
+        assert_eq!(points[2], Point::new(0, 0));
+        assert_eq!(path.steps.last().unwrap().facing, Direction::RIGHT);
+    }
+}
@@ -0,0 +1,311 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use crate::toolbox::grid::{Grid, Point, transform::Symmetry};
+
+// polynomial base for the rolling row hashes, arithmetic wraps mod 2^64
+const BASE: u64 = 1_000_003;
+
+// where a pattern turned up, `at` is the top left of the transformed pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PatternMatch {
+    pub at: Point,
+    pub symmetry: Symmetry,
+}
+
+impl<T: Clone + Eq + Hash> Grid<T> {
+    // every place `pattern` appears under any of `symmetries`, cells of the pattern equal
+    // to `wildcard` match anything, orientations that look the same as an earlier one
+    // are skipped so symmetric patterns aren't counted twice
+    //
+    // rows are compared by rolling hash and a KMP pass runs down each column of window
+    // hashes, so the scan is linear in the grid size, patterns with wildcards search for
+    // their longest solid run that way and check the rest at each hit
+    pub fn find_pattern(
+        &self,
+        pattern: &Grid<T>,
+        wildcard: Option<&T>,
+        symmetries: &[Symmetry],
+    ) -> Vec<PatternMatch> {
+        let codes: Vec<u64> = self.cells.iter().map(code).collect();
+        let mut tried: Vec<Grid<T>> = Vec::new();
+        let mut matches = Vec::new();
+        for &symmetry in symmetries {
+            let oriented = pattern.transformed(symmetry);
+            if tried.contains(&oriented) {
+                continue;
+            }
+            for at in self.find_oriented(&codes, &oriented, wildcard) {
+                matches.push(PatternMatch { at, symmetry });
+            }
+            tried.push(oriented);
+        }
+        matches
+    }
+
+    fn find_oriented(&self, codes: &[u64], pattern: &Grid<T>, wildcard: Option<&T>) -> Vec<Point> {
+        if pattern.is_empty() || pattern.width > self.width || pattern.height > self.height {
+            return Vec::new();
+        }
+        let is_wild = |cell: &T| wildcard == Some(cell);
+        let candidates: Vec<Point> = if !pattern.cells.iter().any(is_wild) {
+            let rows: Vec<Vec<u64>> = pattern
+                .rows()
+                .map(|row| row.iter().map(code).collect())
+                .collect();
+            hashed_matches(codes, self.width, self.height, &rows)
+        } else if let Some((row, start, len)) = longest_solid_run(pattern, &is_wild) {
+            let run: Vec<u64> = pattern.row(row).unwrap()[start..start + len]
+                .iter()
+                .map(code)
+                .collect();
+            hashed_matches(codes, self.width, self.height, &[run])
+                .into_iter()
+                .filter_map(|hit| {
+                    Some(Point::new(
+                        hit.row.checked_sub(row)?,
+                        hit.col.checked_sub(start)?,
+                    ))
+                })
+                .filter(|at| {
+                    at.row + pattern.height <= self.height && at.col + pattern.width <= self.width
+                })
+                .collect()
+        } else {
+            // all wildcards, nothing to anchor on so every placement fits
+            let cols = self.width - pattern.width + 1;
+            (0..(self.height - pattern.height + 1) * cols)
+                .map(|i| Point::new(i / cols, i % cols))
+                .collect()
+        };
+        // hashes can collide, so every candidate gets a real comparison
+        candidates
+            .into_iter()
+            .filter(|&at| {
+                pattern.iter().all(|(p, cell)| {
+                    is_wild(cell) || *cell == self[(at.row + p.row, at.col + p.col)]
+                })
+            })
+            .collect()
+    }
+}
+
+fn code<T: Hash>(cell: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    cell.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn hash_of(codes: &[u64]) -> u64 {
+    codes
+        .iter()
+        .fold(0, |h, &c| h.wrapping_mul(BASE).wrapping_add(c))
+}
+
+// (row, first column, length) of the widest stretch of non-wildcard cells in one row
+fn longest_solid_run<T>(
+    pattern: &Grid<T>,
+    is_wild: &impl Fn(&T) -> bool,
+) -> Option<(usize, usize, usize)> {
+    let mut best: Option<(usize, usize, usize)> = None;
+    for (row, cells) in pattern.rows().enumerate() {
+        let mut start = 0;
+        for col in 0..=cells.len() {
+            if col == cells.len() || is_wild(&cells[col]) {
+                if col > start && best.is_none_or(|(_, _, len)| col - start > len) {
+                    best = Some((row, start, col - start));
+                }
+                start = col + 1;
+            }
+        }
+    }
+    best
+}
+
+// top left corners where every pattern row's hash lines up, possibly false positives
+fn hashed_matches(codes: &[u64], width: usize, height: usize, rows: &[Vec<u64>]) -> Vec<Point> {
+    let (p_width, p_height) = (rows[0].len(), rows.len());
+    let needle: Vec<u64> = rows.iter().map(|row| hash_of(row)).collect();
+    let windows = width - p_width + 1;
+
+    // the hash of every p_width wide window of every grid row, laid out like the grid
+    let top = (1..p_width).fold(1u64, |power, _| power.wrapping_mul(BASE));
+    let mut window_hashes = vec![0; windows * height];
+    for (row, cells) in codes.chunks_exact(width).enumerate() {
+        let mut h = hash_of(&cells[..p_width]);
+        window_hashes[row * windows] = h;
+        for col in 1..windows {
+            h = h
+                .wrapping_sub(cells[col - 1].wrapping_mul(top))
+                .wrapping_mul(BASE)
+                .wrapping_add(cells[col + p_width - 1]);
+            window_hashes[row * windows + col] = h;
+        }
+    }
+
+    let failure = kmp_failure(&needle);
+    let mut found = Vec::new();
+    for col in 0..windows {
+        let column = (0..height).map(|row| window_hashes[row * windows + col]);
+        let mut matched = 0;
+        for (row, h) in column.enumerate() {
+            while matched > 0 && needle[matched] != h {
+                matched = failure[matched - 1];
+            }
+            if needle[matched] == h {
+                matched += 1;
+            }
+            if matched == p_height {
+                found.push(Point::new(row + 1 - p_height, col));
+                matched = failure[matched - 1];
+            }
+        }
+    }
+    found
+}
+
+// failure[i] is the length of the longest proper prefix of needle[..=i] that is also a
+// suffix of it
+fn kmp_failure(needle: &[u64]) -> Vec<usize> {
+    let mut failure = vec![0; needle.len()];
+    let mut k = 0;
+    for i in 1..needle.len() {
+        while k > 0 && needle[i] != needle[k] {
+            k = failure[k - 1];
+        }
+        if needle[i] == needle[k] {
+            k += 1;
+        }
+        failure[i] = k;
+    }
+    failure
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn grid(text: &str) -> Grid<char> {
+        text.parse().unwrap()
+    }
+
+    const WORDS: &str = "\
+MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+    #[test]
+    fn test_exact_rows_and_columns() {
+        let words = grid(WORDS);
+        let xmas = grid("XMAS");
+        let across = words.find_pattern(&xmas, None, &[Symmetry::Identity, Symmetry::FlipH]);
+        assert_eq!(across.len(), 5);
+        assert!(across.contains(&PatternMatch {
+            at: Point::new(0, 5),
+            symmetry: Symmetry::Identity
+        }));
+        assert!(across.contains(&PatternMatch {
+            at: Point::new(1, 1),
+            symmetry: Symmetry::FlipH
+        }));
+        // the four straight directions are the four rotations
+        let straight = [
+            Symmetry::Identity,
+            Symmetry::RotateCw,
+            Symmetry::Rotate180,
+            Symmetry::RotateCcw,
+        ];
+        assert_eq!(words.find_pattern(&xmas, None, &straight).len(), 8);
+    }
+
+    #[test]
+    fn test_wildcards_and_duplicate_orientations() {
+        let words = grid(WORDS);
+        let cross = grid("M.S\n.A.\nM.S");
+        // the flips repeat the rotations, so all 8 still count each cross once
+        assert_eq!(
+            words
+                .find_pattern(&cross, Some(&'.'), &Symmetry::all())
+                .len(),
+            9
+        );
+        assert_eq!(
+            words
+                .find_pattern(&cross, Some(&'.'), &[Symmetry::Identity])
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_sea_monster() {
+        let monster = grid("                  # \n#    ##    ##    ###\n #  #  #  #  #  #   ")
+            .map(|&c| if c == '#' { '#' } else { '?' });
+        let mut sea = Grid::new(24, 5, '.');
+        for (p, &cell) in monster.iter() {
+            if cell == '#' {
+                sea[(p.row + 1, p.col + 2)] = '#';
+            }
+        }
+        sea[(0, 0)] = '#';
+        let found = sea.find_pattern(&monster, Some(&'?'), &Symmetry::all());
+        assert_eq!(
+            found,
+            vec![PatternMatch {
+                at: Point::new(1, 2),
+                symmetry: Symmetry::Identity
+            }]
+        );
+        let turned = sea.flip_h().rotate_cw();
+        assert_eq!(
+            turned
+                .find_pattern(&monster, Some(&'?'), &Symmetry::all())
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_pattern_too_big() {
+        assert!(
+            grid("ab")
+                .find_pattern(&grid("abc"), None, &Symmetry::all())
+                .is_empty()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_matches_naive_scan(
+            cells in prop::collection::vec(0u8..3, 36),
+            pattern in prop::collection::vec(0u8..4, 4),
+        ) {
+            // 3 is the wildcard
+            let haystack = Grid::from_vec(6, 6, cells).unwrap();
+            let pattern = Grid::from_vec(2, 2, pattern).unwrap();
+            let mut found: Vec<Point> = haystack
+                .find_pattern(&pattern, Some(&3), &[Symmetry::Identity])
+                .into_iter()
+                .map(|m| m.at)
+                .collect();
+            found.sort();
+            let naive: Vec<Point> = haystack
+                .points()
+                .filter(|at| at.row + 2 <= 6 && at.col + 2 <= 6)
+                .filter(|at| pattern.iter().all(|(p, &c)| c == 3 || c == haystack[(at.row + p.row, at.col + p.col)]))
+                .collect();
+            prop_assert_eq!(found, naive);
+        }
+    }
+}
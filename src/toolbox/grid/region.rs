@@ -0,0 +1,215 @@
+use std::collections::VecDeque;
+
+use crate::toolbox::grid::{
+    Grid, Point,
+    neighbors::{Edges, Neighborhood},
+};
+
+// one connected group of equal cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    // first cell in reading order, `grid[start]` is the region's value
+    pub start: Point,
+    pub area: usize,
+    // cell edges facing something outside the region, the grid border included
+    pub perimeter: usize,
+    // straight runs of perimeter, counted as corners since a closed outline has as many
+    // corners as sides
+    pub sides: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regions {
+    // region id of every cell, an index into `regions`
+    pub labels: Grid<usize>,
+    pub regions: Vec<Region>,
+}
+
+impl<T> Grid<T> {
+    // every cell reachable from `start` through cells `predicate` accepts, in the order
+    // they were reached, empty when `start` itself isn't accepted
+    pub fn flood_fill(
+        &self,
+        start: Point,
+        neighborhood: Neighborhood,
+        predicate: impl Fn(&T) -> bool,
+    ) -> Vec<Point> {
+        let mut seen = Grid::new(self.width, self.height, false);
+        self.fill_from([start], neighborhood, &predicate, &mut seen)
+    }
+
+    // splits the grid into connected groups of equal cells
+    pub fn regions(&self, neighborhood: Neighborhood) -> Regions
+    where
+        T: PartialEq,
+    {
+        let mut labels = Grid::new(self.width, self.height, usize::MAX);
+        let mut seen = Grid::new(self.width, self.height, false);
+        let mut regions = Vec::new();
+        for start in self.points() {
+            if seen[start] {
+                continue;
+            }
+            let value = &self[start];
+            let cells = self.fill_from([start], neighborhood, &|cell| cell == value, &mut seen);
+            for &cell in &cells {
+                labels[cell] = regions.len();
+            }
+            regions.push(Region {
+                start,
+                area: cells.len(),
+                perimeter: 0,
+                sides: 0,
+            });
+        }
+
+        // outlines only care about the four edges of a cell, whatever joined the region
+        let inside = |point: Point, delta: (isize, isize), id: usize| {
+            Edges::Bounded
+                .apply(point, delta, self.width, self.height)
+                .is_some_and(|next| labels[next] == id)
+        };
+        for point in self.points() {
+            let id = labels[point];
+            let region = &mut regions[id];
+            let sides = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+            for (i, &side) in sides.iter().enumerate() {
+                let next = sides[(i + 1) % 4];
+                let (a, b) = (inside(point, side, id), inside(point, next, id));
+                let diagonal = inside(point, (side.0 + next.0, side.1 + next.1), id);
+                region.perimeter += usize::from(!a);
+                // convex corner, or concave where both sides are in but the diagonal isn't
+                region.sides += usize::from((!a && !b) || (a && b && !diagonal));
+            }
+        }
+        Regions { labels, regions }
+    }
+
+    // cells `open` accepts that can't reach the border through other open cells, like
+    // the tiles enclosed by a loop of walls
+    pub fn enclosed(&self, neighborhood: Neighborhood, open: impl Fn(&T) -> bool) -> Grid<bool> {
+        let mut outside = Grid::new(self.width, self.height, false);
+        let border = self.points().filter(|p| {
+            p.row == 0 || p.col == 0 || p.row + 1 == self.height || p.col + 1 == self.width
+        });
+        self.fill_from(border, neighborhood, &open, &mut outside);
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self
+                .cells
+                .iter()
+                .zip(outside.cells)
+                .map(|(cell, outside)| !outside && open(cell))
+                .collect(),
+        }
+    }
+
+    // BFS from every accepted start at once, marking what it reaches in `seen`
+    fn fill_from(
+        &self,
+        starts: impl IntoIterator<Item = Point>,
+        neighborhood: Neighborhood,
+        predicate: &impl Fn(&T) -> bool,
+        seen: &mut Grid<bool>,
+    ) -> Vec<Point> {
+        let mut queue = VecDeque::new();
+        for start in starts {
+            if self.get(start).is_some_and(predicate) && !seen[start] {
+                seen[start] = true;
+                queue.push_back(start);
+            }
+        }
+        let mut reached = Vec::new();
+        while let Some(point) = queue.pop_front() {
+            reached.push(point);
+            for next in self.neighbors(point, neighborhood, Edges::Bounded) {
+                if !seen[next] && predicate(&self[next]) {
+                    seen[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+        reached
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(text: &str) -> Grid<char> {
+        text.parse().unwrap()
+    }
+
+    // price = area * perimeter and area * sides
+    fn prices(text: &str) -> (usize, usize) {
+        let regions = grid(text).regions(Neighborhood::VonNeumann).regions;
+        (
+            regions.iter().map(|r| r.area * r.perimeter).sum(),
+            regions.iter().map(|r| r.area * r.sides).sum(),
+        )
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let g = grid("..#\n.##\n#..");
+        let mut filled = g.flood_fill(Point::new(0, 0), Neighborhood::VonNeumann, |&c| c == '.');
+        filled.sort();
+        assert_eq!(
+            filled,
+            vec![Point::new(0, 0), Point::new(0, 1), Point::new(1, 0)]
+        );
+        let diagonal = g.flood_fill(Point::new(0, 0), Neighborhood::Moore, |&c| c == '.');
+        assert_eq!(diagonal.len(), 5);
+        assert!(
+            g.flood_fill(Point::new(0, 2), Neighborhood::VonNeumann, |&c| c == '.')
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_region_labels() {
+        let g = grid("AAB\nABB\nCCB");
+        let Regions { labels, regions } = g.regions(Neighborhood::VonNeumann);
+        assert_eq!(regions.len(), 3);
+        assert_eq!(labels.cells(), &[0, 0, 1, 0, 1, 1, 2, 2, 1]);
+        assert_eq!(g[regions[1].start], 'B');
+        assert_eq!(
+            regions.iter().map(|r| r.area).collect::<Vec<_>>(),
+            vec![3, 4, 2]
+        );
+    }
+
+    #[test]
+    fn test_garden_examples() {
+        assert_eq!(prices("AAAA\nBBCD\nBBCC\nEEEC"), (140, 80));
+        assert_eq!(prices("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO"), (772, 436));
+        assert_eq!(prices("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE"), (692, 236));
+        assert_eq!(
+            prices("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA"),
+            (1184, 368)
+        );
+    }
+
+    #[test]
+    fn test_enclosed() {
+        let g = grid("#####.\n#...#.\n#.#.#.\n#####.\n..#...");
+        let inside = g.enclosed(Neighborhood::VonNeumann, |&c| c != '#');
+        let points: Vec<Point> = inside.points().filter(|&p| inside[p]).collect();
+        assert_eq!(
+            points,
+            vec![
+                Point::new(1, 1),
+                Point::new(1, 2),
+                Point::new(1, 3),
+                Point::new(2, 1),
+                Point::new(2, 3)
+            ]
+        );
+        // a diagonal gap lets the inside leak out when diagonals count
+        let leaky = grid("###\n#.#\n##.");
+        assert!(leaky.enclosed(Neighborhood::VonNeumann, |&c| c == '.')[(1, 1)]);
+        assert!(!leaky.enclosed(Neighborhood::Moore, |&c| c == '.')[(1, 1)]);
+    }
+}
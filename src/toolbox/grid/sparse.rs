@@ -0,0 +1,245 @@
+use std::{fmt, ops::Deref};
+
+use crate::{
+    toolbox::{
+        grid::{Direction, Grid, neighbors::Neighborhood},
+        sparse_map::SparseMap,
+    },
+    utils::math_utils::Point2D,
+};
+
+// an unbounded plane keyed by (x, y), y grows downwards like a Grid row, cells that were
+// never set read as `default`, reads go straight to the SparseMap underneath while
+// writes go through here to keep the bounds up to date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: SparseMap<Point2D, T>,
+    // (min, max) corners over the occupied cells, both inclusive
+    bounds: Option<(Point2D, Point2D)>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        SparseGrid {
+            cells: SparseMap::new(default),
+            bounds: None,
+        }
+    }
+
+    // like SparseMap::set, also growing the bounds
+    pub fn set(&mut self, point: Point2D, value: T) -> Option<T> {
+        self.bounds = Some(grow(self.bounds, point));
+        self.cells.set(point, value)
+    }
+
+    pub fn remove(&mut self, point: Point2D) -> Option<T> {
+        let removed = self.cells.remove(point)?;
+        // only a cell on the edge of the box can shrink it
+        if let Some((min, max)) = self.bounds
+            && (point.0 == min.0 || point.0 == max.0 || point.1 == min.1 || point.1 == max.1)
+        {
+            self.bounds = self
+                .cells
+                .keys()
+                .fold(None, |bounds, p| Some(grow(bounds, p)));
+        }
+        Some(removed)
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.bounds = None;
+    }
+
+    // smallest box holding every occupied cell as inclusive (min, max) corners
+    pub fn bounds(&self) -> Option<(Point2D, Point2D)> {
+        self.bounds
+    }
+
+    // every cell next to `point`, there is no edge to stop at
+    pub fn neighbors<'a>(
+        &self,
+        point: Point2D,
+        neighborhood: Neighborhood<'a>,
+    ) -> impl Iterator<Item = Point2D> + use<'a, T> {
+        neighborhood
+            .offsets()
+            .iter()
+            .map(move |&(d_row, d_col)| (point.0 + d_col as i128, point.1 + d_row as i128))
+    }
+
+    pub fn step(&self, point: Point2D, direction: Direction) -> Point2D {
+        let (d_row, d_col) = direction.delta();
+        (point.0 + d_col as i128, point.1 + d_row as i128)
+    }
+
+    // how many cells the bounding box covers, None while nothing is set or when the count
+    // doesn't fit in a usize
+    pub fn area(&self) -> Option<usize> {
+        let (min, max) = self.bounds?;
+        let side = |lo: i128, hi: i128| {
+            hi.checked_sub(lo)
+                .and_then(|span| span.checked_add(1))
+                .and_then(|len| usize::try_from(len).ok())
+        };
+        side(min.0, max.0)?.checked_mul(side(min.1, max.1)?)
+    }
+
+    // the bounding box as a dense Grid plus the (x, y) of its top left cell, None while
+    // nothing is set or when the box covers more than `max_cells`
+    pub fn window(&self, max_cells: usize) -> Option<(Grid<T>, Point2D)>
+    where
+        T: Clone,
+    {
+        let (min, max) = self.bounds?;
+        self.area().filter(|&area| area <= max_cells)?;
+        let width = (max.0 - min.0 + 1) as usize;
+        let height = (max.1 - min.1 + 1) as usize;
+        let grid = Grid::from_fn(width, height, |p| {
+            self.get((min.0 + p.col as i128, min.1 + p.row as i128))
+                .clone()
+        });
+        Some((grid, min))
+    }
+}
+
+fn grow(bounds: Option<(Point2D, Point2D)>, point: Point2D) -> (Point2D, Point2D) {
+    match bounds {
+        None => (point, point),
+        Some((min, max)) => (
+            (min.0.min(point.0), min.1.min(point.1)),
+            (max.0.max(point.0), max.1.max(point.1)),
+        ),
+    }
+}
+
+impl<T> Deref for SparseGrid<T> {
+    type Target = SparseMap<Point2D, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cells
+    }
+}
+
+impl<T: Default> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid::new(T::default())
+    }
+}
+
+impl<T: Default> FromIterator<(Point2D, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point2D, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::default();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(Point2D, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point2D, T)>>(&mut self, iter: I) {
+        for (point, value) in iter {
+            self.set(point, value);
+        }
+    }
+}
+
+// anything bigger is summarised rather than drawn
+const DISPLAY_MAX_CELLS: usize = 1 << 20;
+
+// draws the bounding window row by row, nothing at all when empty
+impl<T: Copy + Into<char>> fmt::Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((min, max)) = self.bounds else {
+            return Ok(());
+        };
+        if self.area().is_none_or(|area| area > DISPLAY_MAX_CELLS) {
+            return write!(
+                f,
+                "{} cells set between {:?} and {:?}",
+                self.len(),
+                min,
+                max
+            );
+        }
+        for y in min.1..=max.1 {
+            if y > min.1 {
+                writeln!(f)?;
+            }
+            for x in min.0..=max.0 {
+                write!(f, "{}", (*self.get((x, y))).into())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plane() -> SparseGrid<char> {
+        let mut grid = SparseGrid::new('.');
+        grid.set((-2, 5), '#');
+        grid.set((1, 3), '#');
+        grid.set((0, 4), 'o');
+        grid
+    }
+
+    #[test]
+    fn test_get_and_bounds() {
+        let grid = plane();
+        assert_eq!(grid.len(), 3);
+        assert_eq!(*grid.get((1, 3)), '#');
+        assert_eq!(*grid.get((1_000_000_000_000, -7)), '.');
+        assert!(!grid.contains((5, 5)));
+        assert_eq!(grid.bounds(), Some(((-2, 3), (1, 5))));
+    }
+
+    #[test]
+    fn test_remove_shrinks_bounds() {
+        let mut grid = plane();
+        assert_eq!(grid.remove((-2, 5)), Some('#'));
+        assert_eq!(grid.bounds(), Some(((0, 3), (1, 4))));
+        assert_eq!(grid.remove((-2, 5)), None);
+        grid.remove((1, 3));
+        grid.remove((0, 4));
+        assert_eq!(grid.bounds(), None);
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn test_window_renders() {
+        let grid = plane();
+        assert_eq!(grid.to_string(), "...#\n..o.\n#...");
+        let (window, origin) = grid.window(12).unwrap();
+        assert_eq!(origin, (-2, 3));
+        assert_eq!(window[(2, 0)], '#');
+        assert_eq!(grid.window(11), None);
+        assert_eq!(SparseGrid::new('.').to_string(), "");
+    }
+
+    #[test]
+    fn test_huge_bounds_are_summarised() {
+        let mut grid = plane();
+        grid.set((1_000_000_000_000, -7), '#');
+        assert_eq!(grid.area(), Some(1_000_000_000_003 * 13));
+        assert_eq!(grid.window(1 << 30), None);
+        assert_eq!(
+            grid.to_string(),
+            "4 cells set between (-2, -7) and (1000000000000, 5)"
+        );
+        grid.set((i128::MIN, i128::MAX), '#');
+        assert_eq!(grid.area(), None);
+        assert!(grid.window(usize::MAX).is_none());
+        assert!(grid.to_string().starts_with("5 cells set between"));
+    }
+
+    #[test]
+    fn test_neighbors_and_directions() {
+        let grid: SparseGrid<u8> = [((0, 0), 1)].into_iter().collect();
+        let around: Vec<Point2D> = grid.neighbors((0, 0), Neighborhood::VonNeumann).collect();
+        assert_eq!(around, vec![(0, -1), (1, 0), (0, 1), (-1, 0)]);
+        assert_eq!(grid.neighbors((0, 0), Neighborhood::Moore).count(), 8);
+        assert_eq!(grid.step((0, 0), Direction::UpLeft), (-1, -1));
+    }
+}
@@ -0,0 +1,281 @@
+use crate::toolbox::grid::{Grid, Point};
+
+// an axis aligned window into a grid, `top_left` plus a size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub top_left: Point,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub fn new(top_left: impl Into<Point>, width: usize, height: usize) -> Self {
+        Rect {
+            top_left: top_left.into(),
+            width,
+            height,
+        }
+    }
+
+    // the smallest rect covering both corners, in either order
+    pub fn spanning(a: impl Into<Point>, b: impl Into<Point>) -> Self {
+        let (a, b) = (a.into(), b.into());
+        Rect::new(
+            (a.row.min(b.row), a.col.min(b.col)),
+            a.col.abs_diff(b.col) + 1,
+            a.row.abs_diff(b.row) + 1,
+        )
+    }
+
+    pub fn contains(&self, point: impl Into<Point>) -> bool {
+        let point = point.into();
+        (self.top_left.row..self.top_left.row + self.height).contains(&point.row)
+            && (self.top_left.col..self.top_left.col + self.width).contains(&point.col)
+    }
+}
+
+// the 8 ways a grid can be rotated or mirrored onto itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    RotateCw,
+    Rotate180,
+    RotateCcw,
+    FlipH,
+    FlipV,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub fn all() -> [Symmetry; 8] {
+        [
+            Symmetry::Identity,
+            Symmetry::RotateCw,
+            Symmetry::Rotate180,
+            Symmetry::RotateCcw,
+            Symmetry::FlipH,
+            Symmetry::FlipV,
+            Symmetry::Transpose,
+            Symmetry::AntiTranspose,
+        ]
+    }
+
+    // where `point` of a width x height grid ends up after the transform
+    pub fn apply(&self, point: Point, width: usize, height: usize) -> Point {
+        let Point { row, col } = point;
+        match self {
+            Symmetry::Identity => Point::new(row, col),
+            Symmetry::RotateCw => Point::new(col, height - 1 - row),
+            Symmetry::Rotate180 => Point::new(height - 1 - row, width - 1 - col),
+            Symmetry::RotateCcw => Point::new(width - 1 - col, row),
+            Symmetry::FlipH => Point::new(row, width - 1 - col),
+            Symmetry::FlipV => Point::new(height - 1 - row, col),
+            Symmetry::Transpose => Point::new(col, row),
+            Symmetry::AntiTranspose => Point::new(width - 1 - col, height - 1 - row),
+        }
+    }
+
+    // the quarter turns and diagonal mirrors swap width and height
+    pub fn swaps_axes(&self) -> bool {
+        matches!(
+            self,
+            Symmetry::RotateCw
+                | Symmetry::RotateCcw
+                | Symmetry::Transpose
+                | Symmetry::AntiTranspose
+        )
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn rotate_cw(&self) -> Self {
+        self.transformed(Symmetry::RotateCw)
+    }
+
+    pub fn rotate_ccw(&self) -> Self {
+        self.transformed(Symmetry::RotateCcw)
+    }
+
+    pub fn rotate_180(&self) -> Self {
+        let mut cells = self.cells.clone();
+        cells.reverse();
+        Grid { cells, ..*self }
+    }
+
+    // rows become columns
+    pub fn transpose(&self) -> Self {
+        self.transformed(Symmetry::Transpose)
+    }
+
+    // mirrors left to right
+    pub fn flip_h(&self) -> Self {
+        let mut cells = self.cells.clone();
+        for row in cells.chunks_exact_mut(self.width.max(1)) {
+            row.reverse();
+        }
+        Grid { cells, ..*self }
+    }
+
+    // mirrors top to bottom
+    pub fn flip_v(&self) -> Self {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for row in self.rows().rev() {
+            cells.extend_from_slice(row);
+        }
+        Grid { cells, ..*self }
+    }
+
+    pub fn transformed(&self, symmetry: Symmetry) -> Self {
+        let (width, height) = (self.width, self.height);
+        match symmetry {
+            Symmetry::Identity => self.clone(),
+            Symmetry::Rotate180 => self.rotate_180(),
+            Symmetry::FlipH => self.flip_h(),
+            Symmetry::FlipV => self.flip_v(),
+            // the rest swap axes, so walk the output in order and pull each cell from the
+            // source, which keeps the writes sequential
+            Symmetry::RotateCw => {
+                self.remap(height, width, |row, col| (height - 1 - col) * width + row)
+            }
+            Symmetry::RotateCcw => {
+                self.remap(height, width, |row, col| col * width + width - 1 - row)
+            }
+            Symmetry::Transpose => self.remap(height, width, |row, col| col * width + row),
+            Symmetry::AntiTranspose => self.remap(height, width, |row, col| {
+                (height - 1 - col) * width + width - 1 - row
+            }),
+        }
+    }
+
+    // every rotation and reflection, starting with the grid itself
+    pub fn symmetries(&self) -> impl Iterator<Item = (Symmetry, Self)> + '_ {
+        Symmetry::all()
+            .into_iter()
+            .map(|symmetry| (symmetry, self.transformed(symmetry)))
+    }
+
+    // None when the rect doesn't fit inside the grid
+    pub fn subgrid(&self, rect: Rect) -> Option<Self> {
+        let Rect {
+            top_left,
+            width,
+            height,
+        } = rect;
+        if top_left.row + height > self.height || top_left.col + width > self.width {
+            return None;
+        }
+        let mut cells = Vec::with_capacity(width * height);
+        for row in self.rows().skip(top_left.row).take(height) {
+            cells.extend_from_slice(&row[top_left.col..top_left.col + width]);
+        }
+        Grid::from_vec(width, height, cells)
+    }
+
+    // surrounds the grid with `n` cells of `fill` on every side
+    pub fn pad(&self, n: usize, fill: T) -> Self {
+        let width = self.width + 2 * n;
+        let height = self.height + 2 * n;
+        let mut cells = Vec::with_capacity(width * height);
+        cells.resize(n * width, fill.clone());
+        for row in self.rows() {
+            cells.resize(cells.len() + n, fill.clone());
+            cells.extend_from_slice(row);
+            cells.resize(cells.len() + n, fill.clone());
+        }
+        cells.resize(width * height, fill);
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    // builds a width x height grid where `source(row, col)` is the flat index to copy from
+    fn remap(&self, width: usize, height: usize, source: impl Fn(usize, usize) -> usize) -> Self {
+        Grid::from_fn(width, height, |p| self.cells[source(p.row, p.col)].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn grid(text: &str) -> Grid<char> {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_rotations() {
+        let g = grid("abc\ndef");
+        assert_eq!(g.rotate_cw(), grid("da\neb\nfc"));
+        assert_eq!(g.rotate_ccw(), grid("cf\nbe\nad"));
+        assert_eq!(g.rotate_180(), grid("fed\ncba"));
+    }
+
+    #[test]
+    fn test_flips_and_transpose() {
+        let g = grid("abc\ndef");
+        assert_eq!(g.flip_h(), grid("cba\nfed"));
+        assert_eq!(g.flip_v(), grid("def\nabc"));
+        assert_eq!(g.transpose(), grid("ad\nbe\ncf"));
+        assert_eq!(g.transformed(Symmetry::AntiTranspose), grid("fc\neb\nda"));
+    }
+
+    #[test]
+    fn test_symmetries_are_distinct() {
+        let g = grid("ab\ncd");
+        let all: Vec<Grid<char>> = g.symmetries().map(|(_, grid)| grid).collect();
+        assert_eq!(all.len(), 8);
+        for (i, a) in all.iter().enumerate() {
+            assert!(all[i + 1..].iter().all(|b| a != b));
+        }
+    }
+
+    #[test]
+    fn test_subgrid() {
+        let g = grid("abcd\nefgh\nijkl");
+        assert_eq!(g.subgrid(Rect::new((1, 1), 2, 2)), Some(grid("fg\njk")));
+        assert_eq!(
+            g.subgrid(Rect::spanning((2, 3), (1, 2))),
+            Some(grid("gh\nkl"))
+        );
+        assert_eq!(g.subgrid(Rect::new((2, 0), 1, 2)), None);
+        assert!(Rect::new((1, 1), 2, 2).contains((2, 2)));
+        assert!(!Rect::new((1, 1), 2, 2).contains((3, 1)));
+    }
+
+    #[test]
+    fn test_pad() {
+        assert_eq!(grid("ab").pad(1, '.'), grid("....\n.ab.\n...."));
+        assert_eq!(grid("ab").pad(0, '.'), grid("ab"));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_symmetries_match_point_mapping(width in 1usize..12, height in 1usize..12) {
+            let g = Grid::from_fn(width, height, |p| p.row * 100 + p.col);
+            for (symmetry, out) in g.symmetries() {
+                let (w, h) = if symmetry.swaps_axes() { (height, width) } else { (width, height) };
+                prop_assert_eq!((out.width(), out.height()), (w, h));
+                for (point, value) in g.iter() {
+                    prop_assert_eq!(&out[symmetry.apply(point, width, height)], value);
+                }
+            }
+        }
+
+        #[test]
+        fn prop_round_trips(width in 1usize..12, height in 1usize..12) {
+            let g = Grid::from_fn(width, height, |p| p.row * 100 + p.col);
+            prop_assert_eq!(g.rotate_cw().rotate_ccw(), g.clone());
+            prop_assert_eq!(g.rotate_cw().rotate_cw(), g.rotate_180());
+            prop_assert_eq!(g.transpose().transpose(), g.clone());
+            prop_assert_eq!(g.flip_h().flip_h(), g.clone());
+            prop_assert_eq!(g.flip_v().flip_h(), g.rotate_180());
+            let padded = g.pad(2, 0);
+            prop_assert_eq!(padded.subgrid(Rect::new((2, 2), width, height)), Some(g));
+        }
+    }
+}
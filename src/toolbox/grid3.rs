@@ -0,0 +1,658 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    ops::{Deref, DerefMut, Index, IndexMut},
+};
+
+use crate::{
+    toolbox::{grid::Grid, sparse_map::SparseMap},
+    utils::point::Point3,
+};
+
+pub type Voxel = Point3<i64>;
+
+const FACES: [Voxel; 6] = [
+    Point3 { x: -1, y: 0, z: 0 },
+    Point3 { x: 1, y: 0, z: 0 },
+    Point3 { x: 0, y: -1, z: 0 },
+    Point3 { x: 0, y: 1, z: 0 },
+    Point3 { x: 0, y: 0, z: -1 },
+    Point3 { x: 0, y: 0, z: 1 },
+];
+
+const ALL: [Voxel; 26] = all_offsets();
+
+// every offset in the surrounding 3x3x3 cube but the centre
+const fn all_offsets() -> [Voxel; 26] {
+    let mut offsets = [Point3 { x: 0, y: 0, z: 0 }; 26];
+    let (mut n, mut i) = (0, 0);
+    while n < 27 {
+        if n != 13 {
+            offsets[i] = Point3 {
+                x: n / 9 - 1,
+                y: n / 3 % 3 - 1,
+                z: n % 3 - 1,
+            };
+            i += 1;
+        }
+        n += 1;
+    }
+    offsets
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood3 {
+    // the 6 voxels sharing a face
+    Faces,
+    // all 26 voxels sharing a face, edge or corner
+    All,
+}
+
+impl Neighborhood3 {
+    pub fn offsets(&self) -> &'static [Voxel] {
+        match self {
+            Neighborhood3::Faces => &FACES,
+            Neighborhood3::All => &ALL,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    fn of(self, point: Voxel) -> i64 {
+        match self {
+            Axis::X => point.x,
+            Axis::Y => point.y,
+            Axis::Z => point.z,
+        }
+    }
+
+    // the axes a slice's columns and rows follow, with Z slices rows follow y and columns
+    // x, otherwise rows follow z
+    fn plane(self) -> (Axis, Axis) {
+        match self {
+            Axis::X => (Axis::Y, Axis::Z),
+            Axis::Y => (Axis::X, Axis::Z),
+            Axis::Z => (Axis::X, Axis::Y),
+        }
+    }
+
+    // the voxel at `row` and `col` along the axes of `plane` in the slice at `index`
+    fn unslice(self, index: i64, row: i64, col: i64) -> Voxel {
+        match self {
+            Axis::X => Point3::new(index, col, row),
+            Axis::Y => Point3::new(col, index, row),
+            Axis::Z => Point3::new(col, row, index),
+        }
+    }
+}
+
+// a dense box of voxels from (0, 0, 0) to (width, height, depth) exclusive, stored x
+// fastest then y then z
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid3<T> {
+    width: usize,
+    height: usize,
+    depth: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid3<T> {
+    pub fn new(width: usize, height: usize, depth: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid3 {
+            width,
+            height,
+            depth,
+            cells: vec![fill; width * height * depth],
+        }
+    }
+
+    pub fn from_fn(
+        width: usize,
+        height: usize,
+        depth: usize,
+        mut f: impl FnMut(Voxel) -> T,
+    ) -> Self {
+        let mut cells = Vec::with_capacity(width * height * depth);
+        for z in 0..depth {
+            for y in 0..height {
+                for x in 0..width {
+                    cells.push(f(Point3::new(x as i64, y as i64, z as i64)));
+                }
+            }
+        }
+        Grid3 {
+            width,
+            height,
+            depth,
+            cells,
+        }
+    }
+
+    // just big enough to hold every point, the points are moved so their min corner lands
+    // on (0, 0, 0) and that offset is returned alongside, like SparseGrid3::to_dense
+    pub fn from_points(points: impl IntoIterator<Item = Voxel>, empty: T, solid: T) -> (Self, Voxel)
+    where
+        T: Clone,
+    {
+        let points: Vec<Voxel> = points.into_iter().collect();
+        let Some(&first) = points.first() else {
+            return (Grid3::new(0, 0, 0, empty), Point3::default());
+        };
+        let (min, max) = points.iter().fold((first, first), |(min, max), p| {
+            (min.min_components(*p), max.max_components(*p))
+        });
+        let size = max - min;
+        let mut grid = Grid3::new(
+            size.x as usize + 1,
+            size.y as usize + 1,
+            size.z as usize + 1,
+            empty,
+        );
+        for point in points {
+            grid[point - min] = solid.clone();
+        }
+        (grid, min)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn contains(&self, point: Voxel) -> bool {
+        (0..self.width as i64).contains(&point.x)
+            && (0..self.height as i64).contains(&point.y)
+            && (0..self.depth as i64).contains(&point.z)
+    }
+
+    fn index_of(&self, point: Voxel) -> Option<usize> {
+        self.contains(point).then(|| {
+            (point.z as usize * self.height + point.y as usize) * self.width + point.x as usize
+        })
+    }
+
+    pub fn get(&self, point: Voxel) -> Option<&T> {
+        self.index_of(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Voxel) -> Option<&mut T> {
+        self.index_of(point).map(|i| &mut self.cells[i])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Voxel, &T)> {
+        let (width, height) = (self.width, self.height);
+        self.cells.iter().enumerate().map(move |(i, cell)| {
+            let (x, y, z) = (i % width, i / width % height, i / (width * height));
+            (Point3::new(x as i64, y as i64, z as i64), cell)
+        })
+    }
+
+    // neighbours inside the box
+    pub fn neighbors(
+        &self,
+        point: Voxel,
+        neighborhood: Neighborhood3,
+    ) -> impl Iterator<Item = Voxel> + use<'_, T> {
+        neighborhood
+            .offsets()
+            .iter()
+            .map(move |&offset| point + offset)
+            .filter(|&next| self.contains(next))
+    }
+
+    // faces of solid voxels not touching another solid voxel, air pockets included
+    pub fn exposed_faces(&self, solid: impl Fn(&T) -> bool) -> usize {
+        let is_solid = |p: Voxel| self.get(p).is_some_and(&solid);
+        exposed_faces(
+            self.iter().map(|(p, _)| p).filter(|&p| is_solid(p)),
+            is_solid,
+        )
+    }
+
+    // faces that can be reached from outside the box, pockets sealed inside don't count
+    pub fn exterior_faces(&self, solid: impl Fn(&T) -> bool) -> usize {
+        if self.cells.is_empty() {
+            return 0;
+        }
+        let size = Point3::new(self.width as i64, self.height as i64, self.depth as i64);
+        let max = size - Point3::new(1, 1, 1);
+        exterior_faces(Point3::default(), max, |p| self.get(p).is_some_and(&solid))
+    }
+
+    // the 2d cross-section at `index` along `axis`, with Z slices rows follow y and
+    // columns x, otherwise rows follow z
+    pub fn slice(&self, axis: Axis, index: usize) -> Option<Grid<T>>
+    where
+        T: Clone,
+    {
+        let size = Point3::new(self.width as i64, self.height as i64, self.depth as i64);
+        if index as i64 >= axis.of(size) {
+            return None;
+        }
+        let (across, down) = axis.plane();
+        let (width, height) = (across.of(size) as usize, down.of(size) as usize);
+        Some(Grid::from_fn(width, height, |p| {
+            self[axis.unslice(index as i64, p.row as i64, p.col as i64)].clone()
+        }))
+    }
+
+    // every slice along `axis` in order, e.g. the layers of a brick stack bottom up
+    pub fn slices(&self, axis: Axis) -> impl Iterator<Item = Grid<T>> + '_
+    where
+        T: Clone,
+    {
+        let count = match axis {
+            Axis::X => self.width,
+            Axis::Y => self.height,
+            Axis::Z => self.depth,
+        };
+        (0..count).filter_map(move |index| self.slice(axis, index))
+    }
+}
+
+impl<T> Index<Voxel> for Grid3<T> {
+    type Output = T;
+
+    fn index(&self, point: Voxel) -> &T {
+        self.get(point).unwrap_or_else(|| {
+            panic!(
+                "{:?} is outside a {}x{}x{} grid",
+                point, self.width, self.height, self.depth
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Voxel> for Grid3<T> {
+    fn index_mut(&mut self, point: Voxel) -> &mut T {
+        let (width, height, depth) = (self.width, self.height, self.depth);
+        self.get_mut(point).unwrap_or_else(|| {
+            panic!(
+                "{:?} is outside a {}x{}x{} grid",
+                point, width, height, depth
+            )
+        })
+    }
+}
+
+// unbounded voxels, for coordinates that are negative or far apart, unset voxels read
+// as `default`, anything that would fill in the bounding box takes a cap on its size so
+// a few voxels far apart can't ask for an enormous allocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid3<T> {
+    cells: SparseMap<Voxel, T>,
+}
+
+impl<T> SparseGrid3<T> {
+    pub fn new(default: T) -> Self {
+        SparseGrid3 {
+            cells: SparseMap::new(default),
+        }
+    }
+
+    // inclusive (min, max) corners over the set voxels
+    pub fn bounds(&self) -> Option<(Voxel, Voxel)> {
+        let mut points = self.cells.keys();
+        let first = points.next()?;
+        Some(points.fold((first, first), |(min, max), p| {
+            (min.min_components(p), max.max_components(p))
+        }))
+    }
+
+    pub fn neighbors(
+        &self,
+        point: Voxel,
+        neighborhood: Neighborhood3,
+    ) -> impl Iterator<Item = Voxel> + use<T> {
+        neighborhood
+            .offsets()
+            .iter()
+            .map(move |&offset| point + offset)
+    }
+
+    pub fn exposed_faces(&self, solid: impl Fn(&T) -> bool) -> usize {
+        let is_solid = |p: Voxel| solid(self.get(p));
+        exposed_faces(
+            self.iter().map(|(p, _)| p).filter(|&p| is_solid(p)),
+            is_solid,
+        )
+    }
+
+    // only the air touching a solid voxel, across an edge or corner too, is flooded so
+    // the empty space between far apart voxels is never walked, unset voxels are air
+    pub fn exterior_faces(&self, solid: impl Fn(&T) -> bool) -> usize {
+        let solids: HashSet<Voxel> = self
+            .iter()
+            .filter(|(_, value)| solid(value))
+            .map(|(p, _)| p)
+            .collect();
+        let near_solid = |p: Voxel| ALL.iter().any(|&offset| solids.contains(&(p + offset)));
+        // the air just before the lowest solid of every line along x is outside, which
+        // gives every separate clump of voxels a place to start from
+        let mut lowest: HashMap<(i64, i64), i64> = HashMap::new();
+        for p in &solids {
+            let x = lowest.entry((p.y, p.z)).or_insert(p.x);
+            *x = (*x).min(p.x);
+        }
+        let mut seen: HashSet<Voxel> = lowest
+            .iter()
+            .map(|(&(y, z), &x)| Point3::new(x - 1, y, z))
+            .collect();
+        let mut queue: VecDeque<Voxel> = seen.iter().copied().collect();
+        let mut faces = 0;
+        while let Some(point) = queue.pop_front() {
+            for &face in &FACES {
+                let next = point + face;
+                if solids.contains(&next) {
+                    faces += 1;
+                } else if near_solid(next) && seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        faces
+    }
+
+    // how many voxels the bounding box covers, None while nothing is set or when the count
+    // doesn't fit in a usize
+    pub fn volume(&self) -> Option<usize> {
+        let (min, max) = self.bounds()?;
+        span(min.x, max.x)?
+            .checked_mul(span(min.y, max.y)?)?
+            .checked_mul(span(min.z, max.z)?)
+    }
+
+    // the set voxels copied into a dense grid plus the offset they were moved by, None
+    // while nothing is set or when the box covers more than `max_cells`
+    pub fn to_dense(&self, max_cells: usize) -> Option<(Grid3<T>, Voxel)>
+    where
+        T: Clone,
+    {
+        let (min, max) = self.bounds()?;
+        self.volume().filter(|&volume| volume <= max_cells)?;
+        let grid = Grid3::from_fn(
+            span(min.x, max.x)?,
+            span(min.y, max.y)?,
+            span(min.z, max.z)?,
+            |p| self.get(p + min).clone(),
+        );
+        Some((grid, min))
+    }
+
+    // like Grid3::slice across the bounding box, `index` is a coordinate along `axis` and
+    // the slice's top left cell sits at the box's min corner, None outside the box or when
+    // the slice covers more than `max_cells`
+    pub fn slice(&self, axis: Axis, index: i64, max_cells: usize) -> Option<Grid<T>>
+    where
+        T: Clone,
+    {
+        let (min, max) = self.bounds()?;
+        if !(axis.of(min)..=axis.of(max)).contains(&index) {
+            return None;
+        }
+        let (across, down) = axis.plane();
+        let width = span(across.of(min), across.of(max))?;
+        let height = span(down.of(min), down.of(max))?;
+        width
+            .checked_mul(height)
+            .filter(|&area| area <= max_cells)?;
+        Some(Grid::from_fn(width, height, |p| {
+            let row = down.of(min) + p.row as i64;
+            let col = across.of(min) + p.col as i64;
+            self.get(axis.unslice(index, row, col)).clone()
+        }))
+    }
+
+    // every slice along `axis` across the bounding box, lowest coordinate first, nothing
+    // when a slice would cover more than `max_cells`
+    pub fn slices(&self, axis: Axis, max_cells: usize) -> impl Iterator<Item = Grid<T>> + '_
+    where
+        T: Clone,
+    {
+        self.bounds()
+            .into_iter()
+            .flat_map(move |(min, max)| axis.of(min)..=axis.of(max))
+            .map_while(move |index| self.slice(axis, index, max_cells))
+    }
+}
+
+// the number of coordinates from `lo` to `hi` inclusive, None when it doesn't fit in a usize
+fn span(lo: i64, hi: i64) -> Option<usize> {
+    usize::try_from(i128::from(hi) - i128::from(lo) + 1).ok()
+}
+
+// nothing to keep in step with the cells, so the map can be used directly
+impl<T> Deref for SparseGrid3<T> {
+    type Target = SparseMap<Voxel, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cells
+    }
+}
+
+impl<T> DerefMut for SparseGrid3<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.cells
+    }
+}
+
+impl<T: Default> FromIterator<(Voxel, T)> for SparseGrid3<T> {
+    fn from_iter<I: IntoIterator<Item = (Voxel, T)>>(iter: I) -> Self {
+        SparseGrid3 {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> Extend<(Voxel, T)> for SparseGrid3<T> {
+    fn extend<I: IntoIterator<Item = (Voxel, T)>>(&mut self, iter: I) {
+        self.cells.extend(iter);
+    }
+}
+
+fn exposed_faces(solids: impl Iterator<Item = Voxel>, is_solid: impl Fn(Voxel) -> bool) -> usize {
+    solids
+        .map(|p| FACES.iter().filter(|&&face| !is_solid(p + face)).count())
+        .sum()
+}
+
+// floods the air in a box one voxel bigger than (min, max) on every side, so it wraps
+// all the way around, and counts each time it bumps into a solid face
+fn exterior_faces(min: Voxel, max: Voxel, is_solid: impl Fn(Voxel) -> bool) -> usize {
+    let one = Point3::new(1, 1, 1);
+    let (low, high) = (min - one, max + one);
+    let inside = |p: Voxel| {
+        (low.x..=high.x).contains(&p.x)
+            && (low.y..=high.y).contains(&p.y)
+            && (low.z..=high.z).contains(&p.z)
+    };
+    let mut seen = HashSet::from([low]);
+    let mut queue = VecDeque::from([low]);
+    let mut faces = 0;
+    while let Some(point) = queue.pop_front() {
+        for &face in &FACES {
+            let next = point + face;
+            if !inside(next) {
+                continue;
+            }
+            if is_solid(next) {
+                faces += 1;
+            } else if seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    faces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the lava droplet example, one air pocket at 2,2,5
+    const DROPLET: &str =
+        "2,2,2\n1,2,2\n3,2,2\n2,1,2\n2,3,2\n2,2,1\n2,2,3\n2,2,4\n2,2,6\n1,2,5\n3,2,5\n2,1,5\n2,3,5";
+
+    fn droplet() -> Vec<Voxel> {
+        DROPLET
+            .lines()
+            .map(|line| {
+                let v: Vec<i64> = line.split(',').map(|n| n.parse().unwrap()).collect();
+                Point3::new(v[0], v[1], v[2])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(ALL.len(), 26);
+        assert!(!ALL.contains(&Point3::default()));
+        assert!(FACES.iter().all(|face| ALL.contains(face)));
+    }
+
+    #[test]
+    fn test_dense_surface() {
+        let (grid, offset) = Grid3::from_points(droplet(), false, true);
+        assert_eq!(offset, Point3::new(1, 1, 1));
+        assert_eq!((grid.width(), grid.height(), grid.depth()), (3, 3, 6));
+        assert_eq!(grid.exposed_faces(|&solid| solid), 64);
+        assert_eq!(grid.exterior_faces(|&solid| solid), 58);
+    }
+
+    #[test]
+    fn test_sparse_surface() {
+        // shifted into negative coordinates, which the dense grid can't hold
+        let shift = Point3::new(-10, -20, 5);
+        let sparse: SparseGrid3<bool> = droplet().into_iter().map(|p| (p + shift, true)).collect();
+        assert_eq!(sparse.exposed_faces(|&solid| solid), 64);
+        assert_eq!(sparse.exterior_faces(|&solid| solid), 58);
+        let (dense, offset) = sparse.to_dense(1 << 20).unwrap();
+        assert_eq!(offset, Point3::new(1, 1, 1) + shift);
+        assert_eq!(dense.exterior_faces(|&solid| solid), 58);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid3::new(3, 3, 3, 0u8);
+        let centre = Point3::new(1, 1, 1);
+        assert_eq!(grid.neighbors(centre, Neighborhood3::Faces).count(), 6);
+        assert_eq!(grid.neighbors(centre, Neighborhood3::All).count(), 26);
+        assert_eq!(
+            grid.neighbors(Point3::default(), Neighborhood3::All)
+                .count(),
+            7
+        );
+        let sparse = SparseGrid3::new(0u8);
+        assert_eq!(
+            sparse
+                .neighbors(Point3::default(), Neighborhood3::All)
+                .count(),
+            26
+        );
+    }
+
+    #[test]
+    fn test_slices() {
+        let grid = Grid3::from_fn(2, 3, 4, |p| p.x * 100 + p.y * 10 + p.z);
+        let layer = grid.slice(Axis::Z, 2).unwrap();
+        assert_eq!((layer.width(), layer.height()), (2, 3));
+        assert_eq!(layer[(1, 0)], 12);
+        let side = grid.slice(Axis::X, 1).unwrap();
+        assert_eq!((side.width(), side.height()), (3, 4));
+        assert_eq!(side[(3, 2)], 123);
+        let front = grid.slice(Axis::Y, 0).unwrap();
+        assert_eq!(front[(1, 1)], 101);
+        assert_eq!(grid.slices(Axis::Z).count(), 4);
+        assert!(grid.slice(Axis::Y, 3).is_none());
+    }
+
+    #[test]
+    fn test_from_negative_points() {
+        let points = [Point3::new(-3, 0, 2), Point3::new(-1, -2, 2)];
+        let (grid, offset) = Grid3::from_points(points, '.', '#');
+        assert_eq!(offset, Point3::new(-3, -2, 2));
+        assert_eq!((grid.width(), grid.height(), grid.depth()), (3, 3, 1));
+        assert!(points.iter().all(|&p| grid[p - offset] == '#'));
+        assert_eq!(grid.iter().filter(|&(_, &c)| c == '#').count(), 2);
+        let (empty, _) = Grid3::from_points([], '.', '#');
+        assert_eq!(empty.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_sparse_slices() {
+        let mut sparse = SparseGrid3::new('.');
+        sparse.set(Point3::new(-1, 4, -7), 'a');
+        sparse.set(Point3::new(1, 5, -7), 'b');
+        sparse.set(Point3::new(0, 4, -6), 'c');
+        let bottom = sparse.slice(Axis::Z, -7, 100).unwrap();
+        assert_eq!(bottom.to_string(), "a..\n..b");
+        assert_eq!(
+            sparse.slice(Axis::Z, -6, 100).unwrap().to_string(),
+            ".c.\n..."
+        );
+        assert!(sparse.slice(Axis::Z, -5, 100).is_none());
+        assert!(sparse.slice(Axis::Z, -7, 5).is_none());
+        assert_eq!(sparse.slice(Axis::X, 1, 100).unwrap().to_string(), ".b\n..");
+        assert_eq!(sparse.slices(Axis::Y, 100).count(), 2);
+        // slicing the dense copy gives the same grids
+        let (dense, _) = sparse.to_dense(100).unwrap();
+        assert!(sparse.slices(Axis::Z, 100).eq(dense.slices(Axis::Z)));
+        assert_eq!(SparseGrid3::new('.').slices(Axis::X, 100).count(), 0);
+    }
+
+    #[test]
+    fn test_sparse_far_apart() {
+        let mut sparse = SparseGrid3::new(false);
+        sparse.set(Point3::new(0, 0, 0), true);
+        sparse.set(Point3::new(1_000_000, -1_000_000, 1_000_000), true);
+        assert_eq!(sparse.exterior_faces(|&solid| solid), 12);
+        assert_eq!(sparse.volume(), Some(1_000_001usize.pow(3)));
+        assert!(sparse.to_dense(1 << 20).is_none());
+        assert!(sparse.slice(Axis::Z, 0, 1 << 20).is_none());
+        assert_eq!(sparse.slices(Axis::Z, 1 << 20).count(), 0);
+
+        sparse.set(Point3::new(i64::MIN, 0, 0), true);
+        sparse.set(Point3::new(i64::MAX, 0, 0), true);
+        assert_eq!(sparse.volume(), None);
+        assert!(sparse.to_dense(usize::MAX).is_none());
+    }
+
+    #[test]
+    fn test_sparse_exterior_matches_dense() {
+        // a hollow 5x5x5 box with a hole in one wall, plus a sealed 3x3x3 box far away
+        let mut sparse = SparseGrid3::new(false);
+        for x in 0..5 {
+            for y in 0..5 {
+                for z in 0..5 {
+                    let wall = [x, y, z].iter().any(|&c| c == 0 || c == 4);
+                    if wall && (x, y) != (2, 2) {
+                        sparse.set(Point3::new(x, y, z), true);
+                    }
+                    if x < 3 && y < 3 && z < 3 && (x, y, z) != (1, 1, 1) {
+                        sparse.set(Point3::new(x + 50, y, z - 40), true);
+                    }
+                }
+            }
+        }
+        let (dense, _) = sparse.to_dense(1 << 20).unwrap();
+        let exterior = dense.exterior_faces(|&solid| solid);
+        assert_eq!(sparse.exterior_faces(|&solid| solid), exterior);
+        assert!(exterior < sparse.exposed_faces(|&solid| solid));
+    }
+}
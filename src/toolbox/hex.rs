@@ -0,0 +1,605 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    ops::{Add, AddAssign, Deref, DerefMut, Mul, Neg, Sub, SubAssign},
+};
+
+use crate::{
+    toolbox::{
+        automaton::SparseWorld,
+        pathfinding::{self, Path},
+        sparse_map::SparseMap,
+    },
+    utils::parse_error::OffsetError,
+};
+
+// the six axial steps going anticlockwise from east on a pointy grid, which is also
+// anticlockwise from south east on a flat one
+const AXIAL: [Hex; 6] = [
+    Hex::new(1, 0),
+    Hex::new(1, -1),
+    Hex::new(0, -1),
+    Hex::new(-1, 0),
+    Hex::new(-1, 1),
+    Hex::new(0, 1),
+];
+
+// whether the hexes have a flat edge or a point at the top, which decides the names of
+// the six directions, pointy grids go e/ne/nw/w/sw/se and flat ones n/ne/se/s/sw/nw
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    Flat,
+    Pointy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexDirection {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl HexDirection {
+    // the six that exist for an orientation, anticlockwise
+    pub fn all(orientation: Orientation) -> [HexDirection; 6] {
+        use HexDirection::*;
+        match orientation {
+            Orientation::Flat => [SE, NE, N, NW, SW, S],
+            Orientation::Pointy => [E, NE, NW, W, SW, SE],
+        }
+    }
+
+    // None for n/s on a pointy grid and e/w on a flat one
+    pub fn delta(&self, orientation: Orientation) -> Option<Hex> {
+        HexDirection::all(orientation)
+            .iter()
+            .position(|direction| direction == self)
+            .map(|i| AXIAL[i])
+    }
+
+    pub fn opposite(&self) -> HexDirection {
+        use HexDirection::*;
+        match self {
+            N => S,
+            NE => SW,
+            E => W,
+            SE => NW,
+            S => N,
+            SW => NE,
+            W => E,
+            NW => SE,
+        }
+    }
+
+    // a walk such as "ne,sw", "e/se/w" or "esenee", commas, slashes and whitespace between
+    // steps are all optional, errors point at the first step that isn't a direction
+    pub fn parse_list(
+        text: &str,
+        orientation: Orientation,
+    ) -> Result<Vec<HexDirection>, OffsetError> {
+        let mut directions = Vec::new();
+        let mut offset = 0;
+        let bytes = text.as_bytes();
+        while offset < bytes.len() {
+            if matches!(bytes[offset], b',' | b'/') || bytes[offset].is_ascii_whitespace() {
+                offset += 1;
+                continue;
+            }
+            // the longest name wins so "ne" isn't read as "n" then "e"
+            let (direction, width) = [2, 1]
+                .into_iter()
+                .filter_map(|width| {
+                    let name = text.get(offset..offset + width)?;
+                    let direction = HexDirection::from_name(name)?;
+                    direction.delta(orientation).map(|_| (direction, width))
+                })
+                .next()
+                .ok_or_else(|| {
+                    let expected = HexDirection::all(orientation).map(|d| d.name()).join(", ");
+                    OffsetError::new(offset, 1, format!("a hex direction ({})", expected))
+                })?;
+            directions.push(direction);
+            offset += width;
+        }
+        Ok(directions)
+    }
+
+    fn from_name(name: &str) -> Option<HexDirection> {
+        use HexDirection::*;
+        Some(match name.to_ascii_lowercase().as_str() {
+            "n" => N,
+            "ne" => NE,
+            "e" => E,
+            "se" => SE,
+            "s" => S,
+            "sw" => SW,
+            "w" => W,
+            "nw" => NW,
+            _ => return None,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        use HexDirection::*;
+        match self {
+            N => "n",
+            NE => "ne",
+            E => "e",
+            SE => "se",
+            S => "s",
+            SW => "sw",
+            W => "w",
+            NW => "nw",
+        }
+    }
+}
+
+// axial coordinates, the third cube coordinate s is always -q - r so it isn't stored,
+// r grows downwards like a Grid row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Hex {
+    pub q: i64,
+    pub r: i64,
+}
+
+// the same position with all three coordinates, q + r + s == 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Cube {
+    pub q: i64,
+    pub r: i64,
+    pub s: i64,
+}
+
+impl Hex {
+    pub const ZERO: Hex = Hex::new(0, 0);
+
+    pub const fn new(q: i64, r: i64) -> Self {
+        Hex { q, r }
+    }
+
+    pub fn s(&self) -> i64 {
+        -self.q - self.r
+    }
+
+    pub fn to_cube(self) -> Cube {
+        Cube {
+            q: self.q,
+            r: self.r,
+            s: self.s(),
+        }
+    }
+
+    // None when `direction` doesn't exist in `orientation`
+    pub fn step(self, direction: HexDirection, orientation: Orientation) -> Option<Hex> {
+        Some(self + direction.delta(orientation)?)
+    }
+
+    // where a list of steps ends up, None if any of them doesn't exist in `orientation`
+    pub fn walk(self, directions: &[HexDirection], orientation: Orientation) -> Option<Hex> {
+        directions
+            .iter()
+            .try_fold(self, |hex, &direction| hex.step(direction, orientation))
+    }
+
+    // the six around this one, the same cells whichever way up the grid is
+    pub fn neighbors(self) -> [Hex; 6] {
+        AXIAL.map(|delta| self + delta)
+    }
+
+    // fewest steps between the two
+    pub fn distance(self, other: Hex) -> u64 {
+        let d = (self - other).to_cube();
+        (d.q.unsigned_abs() + d.r.unsigned_abs() + d.s.unsigned_abs()) / 2
+    }
+
+    // a sixth of a turn about the origin
+    pub fn rotate_cw(self) -> Hex {
+        Hex::new(-self.r, -self.s())
+    }
+
+    pub fn rotate_ccw(self) -> Hex {
+        Hex::new(-self.s(), -self.q)
+    }
+
+    // every hex exactly `radius` away, going round anticlockwise, just this one at 0
+    pub fn ring(self, radius: u64) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![self];
+        }
+        let radius = radius as i64;
+        let mut hex = self + AXIAL[4] * radius;
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+        for side in AXIAL {
+            for _ in 0..radius {
+                ring.push(hex);
+                hex += side;
+            }
+        }
+        ring
+    }
+
+    // this one then every ring out to `radius`, nearest first
+    pub fn spiral(self, radius: u64) -> Vec<Hex> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+}
+
+impl Cube {
+    pub fn new(q: i64, r: i64, s: i64) -> Self {
+        debug_assert_eq!(q + r + s, 0, "cube coordinates must sum to zero");
+        Cube { q, r, s }
+    }
+
+    pub fn distance(self, other: Cube) -> u64 {
+        Hex::from(self).distance(Hex::from(other))
+    }
+}
+
+impl From<Hex> for Cube {
+    fn from(hex: Hex) -> Self {
+        hex.to_cube()
+    }
+}
+
+impl From<Cube> for Hex {
+    fn from(cube: Cube) -> Self {
+        Hex::new(cube.q, cube.r)
+    }
+}
+
+impl From<(i64, i64)> for Hex {
+    fn from((q, r): (i64, i64)) -> Self {
+        Hex::new(q, r)
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+    fn add(self, other: Hex) -> Hex {
+        Hex::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+    fn sub(self, other: Hex) -> Hex {
+        Hex::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+impl Mul<i64> for Hex {
+    type Output = Hex;
+    fn mul(self, k: i64) -> Hex {
+        Hex::new(self.q * k, self.r * k)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+    fn neg(self) -> Hex {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, other: Hex) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Hex {
+    fn sub_assign(&mut self, other: Hex) {
+        *self = *self - other;
+    }
+}
+
+// which hexes count as next to a hex, the hex version of the square Neighborhood
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexNeighborhood<'a> {
+    // the six touching hexes
+    Adjacent,
+    // any axial offsets, e.g. the six hexes two steps away
+    Custom(&'a [Hex]),
+}
+
+impl<'a> HexNeighborhood<'a> {
+    pub fn offsets(&self) -> &'a [Hex] {
+        match self {
+            HexNeighborhood::Adjacent => &AXIAL,
+            HexNeighborhood::Custom(offsets) => offsets,
+        }
+    }
+}
+
+// an unbounded hex plane, cells that were never set read as `default`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexMap<T> {
+    cells: SparseMap<Hex, T>,
+}
+
+impl<T> HexMap<T> {
+    pub fn new(default: T) -> Self {
+        HexMap {
+            cells: SparseMap::new(default),
+        }
+    }
+
+    // the cells around `hex` for a neighbourhood
+    pub fn neighbors<'a>(
+        &self,
+        hex: Hex,
+        neighborhood: HexNeighborhood<'a>,
+    ) -> impl Iterator<Item = Hex> + use<'a, T> {
+        neighborhood.offsets().iter().map(move |&delta| hex + delta)
+    }
+
+    // the searches below only walk over cells that were set and that `passable` accepts,
+    // the plane has no edge so the default is never walkable, the start is never checked
+
+    // steps from `start` to every cell it can reach
+    pub fn bfs_distances(&self, start: Hex, passable: impl Fn(&T) -> bool) -> HashMap<Hex, usize> {
+        let mut distances = HashMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some((hex, steps)) = queue.pop_front() {
+            for next in hex.neighbors() {
+                if !distances.contains_key(&next) && self.occupied(next).is_some_and(&passable) {
+                    distances.insert(next, steps + 1);
+                    queue.push_back((next, steps + 1));
+                }
+            }
+        }
+        distances
+    }
+
+    // fewest steps from `start` to `goal`, guided by the hex distance
+    pub fn shortest_path(
+        &self,
+        start: Hex,
+        goal: Hex,
+        passable: impl Fn(&T) -> bool,
+    ) -> Option<Path<Hex>> {
+        pathfinding::astar(
+            start,
+            |&hex| {
+                hex.neighbors()
+                    .into_iter()
+                    .filter(|next| self.occupied(*next).is_some_and(&passable))
+                    .map(|next| (next, 1))
+                    .collect::<Vec<_>>()
+            },
+            |&hex| hex.distance(goal),
+            |&hex| hex == goal,
+        )
+    }
+}
+
+// nothing to keep in step with the cells, so the map can be used directly
+impl<T> Deref for HexMap<T> {
+    type Target = SparseMap<Hex, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cells
+    }
+}
+
+impl<T> DerefMut for HexMap<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.cells
+    }
+}
+
+impl<T: Default> Default for HexMap<T> {
+    fn default() -> Self {
+        HexMap::new(T::default())
+    }
+}
+
+impl<T: Default> FromIterator<(Hex, T)> for HexMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Hex, T)>>(iter: I) -> Self {
+        HexMap {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> Extend<(Hex, T)> for HexMap<T> {
+    fn extend<I: IntoIterator<Item = (Hex, T)>>(&mut self, iter: I) {
+        self.cells.extend(iter);
+    }
+}
+
+impl<T: Clone + PartialEq> SparseWorld for HexMap<T> {
+    type Key = Hex;
+    type Cell = T;
+    type Neighborhood<'a> = HexNeighborhood<'a>;
+
+    fn cells(&self) -> impl Iterator<Item = (Hex, &T)> {
+        self.iter()
+    }
+
+    fn cell(&self, key: Hex) -> &T {
+        self.get(key)
+    }
+
+    fn default_cell(&self) -> &T {
+        SparseMap::default_cell(self)
+    }
+
+    fn neighbor_keys<'a>(
+        &self,
+        key: Hex,
+        neighborhood: Self::Neighborhood<'a>,
+    ) -> impl Iterator<Item = Hex> {
+        self.neighbors(key, neighborhood)
+    }
+
+    fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    fn insert(&mut self, key: Hex, cell: T) {
+        self.set(key, cell);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::toolbox::automaton::Automaton;
+
+    fn flat_walk(text: &str) -> Hex {
+        let steps = HexDirection::parse_list(text, Orientation::Flat).unwrap();
+        Hex::ZERO.walk(&steps, Orientation::Flat).unwrap()
+    }
+
+    #[test]
+    fn test_flat_walk_distances() {
+        assert_eq!(flat_walk("ne,ne,ne").distance(Hex::ZERO), 3);
+        assert_eq!(flat_walk("ne,ne,sw,sw").distance(Hex::ZERO), 0);
+        assert_eq!(flat_walk("ne,ne,s,s").distance(Hex::ZERO), 2);
+        assert_eq!(flat_walk("se,sw,se,sw,sw").distance(Hex::ZERO), 3);
+    }
+
+    #[test]
+    fn test_parse_pointy_directions() {
+        use HexDirection::*;
+        let pointy = Orientation::Pointy;
+        assert_eq!(
+            HexDirection::parse_list("e/se/w", pointy).unwrap(),
+            vec![E, SE, W]
+        );
+        assert_eq!(
+            HexDirection::parse_list("esenee", pointy).unwrap(),
+            vec![E, SE, NE, E]
+        );
+        let back_home = HexDirection::parse_list("nwwswee", pointy).unwrap();
+        assert_eq!(Hex::ZERO.walk(&back_home, pointy), Some(Hex::ZERO));
+        // north doesn't exist with a point at the top
+        let err = HexDirection::parse_list("e,n", pointy).unwrap_err();
+        assert_eq!(err.offset, 2);
+        assert!(err.expected.contains("e, ne, nw, w, sw, se"));
+        assert_eq!(N.delta(pointy), None);
+    }
+
+    #[test]
+    fn test_cube_round_trip() {
+        let hex = Hex::new(3, -5);
+        let cube = Cube::from(hex);
+        assert_eq!(cube, Cube::new(3, -5, 2));
+        assert_eq!(Hex::from(cube), hex);
+        assert_eq!(cube.distance(Cube::new(0, 0, 0)), 5);
+    }
+
+    #[test]
+    fn test_rings_and_spirals() {
+        let center = Hex::new(2, -1);
+        assert_eq!(center.ring(0), vec![center]);
+        for radius in 1..5 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert!(ring.iter().all(|&hex| hex.distance(center) == radius));
+        }
+        let spiral = center.spiral(3);
+        assert_eq!(spiral.len(), 37);
+        assert_eq!(spiral.iter().collect::<HashSet<_>>().len(), 37);
+        assert_eq!(spiral[1..7].to_vec(), center.ring(1));
+    }
+
+    #[test]
+    fn test_rotation() {
+        let hex = Hex::new(2, -1);
+        let mut turned = hex;
+        for _ in 0..6 {
+            turned = turned.rotate_cw();
+            assert_eq!(turned.distance(Hex::ZERO), 2);
+        }
+        assert_eq!(turned, hex);
+        assert_eq!(hex.rotate_cw().rotate_ccw(), hex);
+    }
+
+    #[test]
+    fn test_paths_over_set_cells() {
+        // a ring of floor with the middle walled off
+        let mut map = HexMap::new('#');
+        map.extend(Hex::ZERO.ring(2).into_iter().map(|hex| (hex, '.')));
+        map.set(Hex::ZERO, '#');
+        let start = Hex::ZERO.ring(2)[0];
+        let goal = -start;
+        let path = map.shortest_path(start, goal, |&c| c == '.').unwrap();
+        // straight across is 4, round the ring is half of 12
+        assert_eq!(path.cost, 6);
+        let distances = map.bfs_distances(start, |&c| c == '.');
+        assert_eq!(distances.len(), 12);
+        assert_eq!(distances[&goal], 6);
+        assert!(map.shortest_path(start, Hex::ZERO, |&c| c == '.').is_none());
+    }
+
+    #[test]
+    fn test_lobby_layout_automaton() {
+        let tiles = "\
+sesenwnenenewseeswwswswwnenewsewsw
+neeenesenwnwwswnenewnwwsewnenwseswesw
+seswneswswsenwwnwse
+nwnwneseeswswnenewneswwnewseswneseene
+swweswneswnenwsewnwneneseenw
+eesenwseswswnenwswnwnwsewwnwsene
+sewnenenenesenwsewnenwwwse
+wenwwweseeeweswwwnwwe
+wsweesenenewnwwnwsenewsenwwsesesenwne
+neeswseenwwswnwswswnw
+nenwswwsewswnenenewsenwsenwnesesenew
+enewnwewneswsewnwswenweswnenwsenwsw
+sweneswneswneneenwnewenewwneswswnese
+swwesenesewenwneswnwwneseswwne
+enesenwswwswneneswsenwnewswseenwsese
+wnwnesenesenenwwnenwsewesewsesesew
+nenewswnwewswnenesenwnesewesw
+eneswnwswnwsenenwnwnwwseeswneewsenese
+neswnwewnwnwseenwseesewsenwsweewe
+wseweeenwnesenwwwswnew";
+        let mut black: HexMap<bool> = HexMap::default();
+        for line in tiles.lines() {
+            let steps = HexDirection::parse_list(line, Orientation::Pointy).unwrap();
+            let tile = Hex::ZERO.walk(&steps, Orientation::Pointy).unwrap();
+            let flipped = !black.get(tile);
+            if flipped {
+                black.set(tile, true);
+            } else {
+                black.remove(tile);
+            }
+        }
+        assert_eq!(black.len(), 10);
+
+        // black tiles stay black next to 1 or 2 others, white ones turn black next to 2
+        let rule = |&tile: &bool, around: &[&bool]| {
+            matches!(
+                (tile, around.iter().filter(|&&&b| b).count()),
+                (true, 1 | 2) | (false, 2)
+            )
+        };
+        let mut days = Automaton::new(black, HexNeighborhood::Adjacent, rule);
+        days.step();
+        assert_eq!(days.world().len(), 15);
+        days.run_n(10);
+        assert_eq!(days.world().len(), 37);
+    }
+
+    #[test]
+    fn test_neighborhoods() {
+        let map = HexMap::new(0);
+        let center = Hex::new(1, 2);
+        let adjacent: Vec<_> = map.neighbors(center, HexNeighborhood::Adjacent).collect();
+        assert_eq!(adjacent, center.neighbors());
+        let far = [Hex::new(2, -1), Hex::new(-2, 1)];
+        let custom: Vec<_> = map
+            .neighbors(center, HexNeighborhood::Custom(&far))
+            .collect();
+        assert_eq!(custom, vec![Hex::new(3, 1), Hex::new(-1, 3)]);
+    }
+}
@@ -1,4 +1,13 @@
+pub mod animate;
+pub mod automaton;
 pub mod bfs_strategy;
+pub mod columns;
 pub mod combination_safe;
 pub mod dancing_links;
+pub mod expr;
 pub mod grid;
+pub mod grid3;
+pub mod hex;
+pub mod pathfinding;
+pub mod render;
+pub mod sparse_map;
@@ -0,0 +1,140 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    hash::Hash,
+};
+
+// the states from start to goal, both included, and what it cost to walk them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S> {
+    pub cost: u64,
+    pub steps: Vec<S>,
+}
+
+impl<S> Path<S> {
+    // moves taken, one less than the states visited
+    pub fn len(&self) -> usize {
+        self.steps.len().saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// cheapest path from `start` to any state passing `is_goal`, `successors` yields each
+// next state with the cost of moving there
+pub fn dijkstra<S, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S>>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    astar(start, successors, |_| 0, is_goal)
+}
+
+// dijkstra guided by `heuristic`, which must never overestimate the remaining cost or
+// the path found may not be the cheapest
+pub fn astar<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> u64,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S>>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    // states live in `states` and everything else refers to them by index, so S
+    // doesn't have to be Ord for the heap
+    let mut states = vec![start.clone()];
+    let mut ids = HashMap::from([(start, 0)]);
+    let mut best = vec![0];
+    let mut parent: Vec<Option<usize>> = vec![None];
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&states[0]), 0, 0))]);
+
+    while let Some(Reverse((_, cost, id))) = heap.pop() {
+        if cost > best[id] {
+            continue;
+        }
+        if is_goal(&states[id]) {
+            let mut steps = vec![states[id].clone()];
+            let mut at = id;
+            while let Some(prev) = parent[at] {
+                steps.push(states[prev].clone());
+                at = prev;
+            }
+            steps.reverse();
+            return Some(Path { cost, steps });
+        }
+        for (next, step_cost) in successors(&states[id]) {
+            let next_cost = cost + step_cost;
+            let next_id = *ids.entry(next.clone()).or_insert_with(|| {
+                states.push(next);
+                best.push(u64::MAX);
+                parent.push(None);
+                states.len() - 1
+            });
+            if next_cost < best[next_id] {
+                best[next_id] = next_cost;
+                parent[next_id] = Some(id);
+                let estimate = next_cost + heuristic(&states[next_id]);
+                heap.push(Reverse((estimate, next_cost, next_id)));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a small weighted graph where the direct edge is the expensive one
+    fn edges(node: &char) -> Vec<(char, u64)> {
+        match node {
+            'a' => vec![('b', 1), ('d', 10)],
+            'b' => vec![('c', 2)],
+            'c' => vec![('d', 3)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_prefers_cheaper_detour() {
+        let path = dijkstra('a', edges, |&node| node == 'd').unwrap();
+        assert_eq!(path.cost, 6);
+        assert_eq!(path.steps, vec!['a', 'b', 'c', 'd']);
+        assert_eq!(path.len(), 3);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        assert_eq!(dijkstra('b', edges, |&node| node == 'a'), None);
+    }
+
+    #[test]
+    fn test_start_is_goal() {
+        let path = dijkstra('a', edges, |&node| node == 'a').unwrap();
+        assert_eq!(path.cost, 0);
+        assert!(path.is_empty());
+    }
+
+    #[test]
+    fn test_astar_on_a_line() {
+        // walking a number line towards 10, steps of 1 or 3 with 3 costing double
+        let successors = |&n: &i32| [(n + 1, 1), (n - 1, 1), (n + 3, 2)];
+        let path = astar(
+            0,
+            successors,
+            |&n| (10 - n).unsigned_abs() as u64 / 3,
+            |&n| n == 10,
+        )
+        .unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.steps.last(), Some(&10));
+    }
+}
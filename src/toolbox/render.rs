@@ -0,0 +1,448 @@
+use std::{
+    fmt, fs,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{solver::options, toolbox::grid::Grid, utils::math_utils::Point2D};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const RED: Rgb = Rgb(220, 40, 40);
+    pub const GREEN: Rgb = Rgb(60, 170, 80);
+    pub const BLUE: Rgb = Rgb(50, 100, 220);
+    pub const GREY: Rgb = Rgb(128, 128, 128);
+}
+
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+// where `name` should be written when the run was started with --render, the directory
+// is created on first use, None when rendering is off
+pub fn output(name: &str) -> Option<io::Result<PathBuf>> {
+    let dir = options::render_dir()?;
+    Some(fs::create_dir_all(dir).map(|_| dir.join(name)))
+}
+
+// maps tile coordinates onto pixels, real inputs can span far more tiles than anyone
+// wants pixels so one pixel may cover `scale` tiles a side
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    min: Point2D,
+    scale: f64,
+    width: usize,
+    height: usize,
+}
+
+impl Viewport {
+    // the smallest viewport holding every point with neither side over `max_side`
+    // pixels, None for no points
+    pub fn fit(points: impl IntoIterator<Item = Point2D>, max_side: usize) -> Option<Self> {
+        let (min, max) = bounds(points)?;
+        let (span_x, span_y) = ((max.0 - min.0 + 1) as f64, (max.1 - min.1 + 1) as f64);
+        let scale = (span_x.max(span_y) / max_side.max(1) as f64).max(1.0);
+        Some(Viewport {
+            min,
+            scale,
+            width: (span_x / scale).ceil() as usize,
+            height: (span_y / scale).ceil() as usize,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // the pixel covering a tile, clamped to the edge for tiles outside the view
+    pub fn pixel(&self, point: Point2D) -> (usize, usize) {
+        let to_pixel =
+            |offset: i128, limit: usize| ((offset as f64 / self.scale) as usize).min(limit - 1);
+        (
+            to_pixel((point.0 - self.min.0).max(0), self.width),
+            to_pixel((point.1 - self.min.1).max(0), self.height),
+        )
+    }
+
+    // the tile coordinate at the centre of a pixel, fractional once scaled down
+    fn center(&self, pixel: usize, min: i128) -> f64 {
+        min as f64 + (pixel as f64 + 0.5) * self.scale - 0.5
+    }
+}
+
+// inclusive (min, max) corners over every point
+fn bounds(points: impl IntoIterator<Item = Point2D>) -> Option<(Point2D, Point2D)> {
+    points.into_iter().fold(None, |bounds, p| match bounds {
+        None => Some((p, p)),
+        Some((min, max)) => Some((
+            (min.0.min(p.0), min.1.min(p.1)),
+            (max.0.max(p.0), max.1.max(p.1)),
+        )),
+    })
+}
+
+// an RGB raster that can be saved as PPM or PNG
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Image {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    pub fn for_view(view: &Viewport, background: Rgb) -> Self {
+        Image::new(view.width, view.height, background)
+    }
+
+    // every cell becomes a `scale` by `scale` block coloured by `color`
+    pub fn from_grid<T>(grid: &Grid<T>, scale: usize, color: impl Fn(&T) -> Rgb) -> Self {
+        let scale = scale.max(1);
+        let mut image = Image::new(grid.width() * scale, grid.height() * scale, Rgb::BLACK);
+        for (point, cell) in grid.iter() {
+            let rgb = color(cell);
+            for y in point.row * scale..(point.row + 1) * scale {
+                image.pixels[y * image.width + point.col * scale..][..scale].fill(rgb);
+            }
+        }
+        image
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        (x < self.width && y < self.height).then(|| self.pixels[y * self.width + x])
+    }
+
+    // pixels off the image are ignored
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = color;
+        }
+    }
+
+    // fills the inside of a closed polygon by the even-odd rule, scanning one pixel row
+    // at a time so huge polygons cost pixels rather than tiles
+    pub fn fill_polygon(&mut self, view: &Viewport, points: &[Point2D], color: Rgb) {
+        let mut crossings = Vec::new();
+        for py in 0..self.height {
+            let y = view.center(py, view.min.1);
+            crossings.clear();
+            for (i, &a) in points.iter().enumerate() {
+                let b = points[(i + 1) % points.len()];
+                let (ay, by) = (a.1 as f64, b.1 as f64);
+                if (ay <= y) != (by <= y) {
+                    crossings.push(a.0 as f64 + (y - ay) / (by - ay) * (b.0 - a.0) as f64);
+                }
+            }
+            crossings.sort_by(f64::total_cmp);
+            for span in crossings.chunks_exact(2) {
+                // first and last pixel whose centre lies within the span
+                let from = ((span[0] - view.min.0 as f64 + 0.5) / view.scale - 0.5).ceil();
+                let to = ((span[1] - view.min.0 as f64 + 0.5) / view.scale - 0.5).floor();
+                if to >= from.max(0.0) {
+                    for px in from.max(0.0) as usize..=to as usize {
+                        self.set(px, py, color);
+                    }
+                }
+            }
+        }
+    }
+
+    // the outline of a closed polygon, one pixel wide
+    pub fn stroke_polygon(&mut self, view: &Viewport, points: &[Point2D], color: Rgb) {
+        for (i, &a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            self.line(view.pixel(a), view.pixel(b), color);
+        }
+    }
+
+    // the filled box with `a` and `b` as opposite corners, both inclusive, clipped to the
+    // image when the view is bigger than it
+    pub fn fill_rect(&mut self, view: &Viewport, a: Point2D, b: Point2D, color: Rgb) {
+        let (x0, y0) = view.pixel((a.0.min(b.0), a.1.min(b.1)));
+        let (x1, y1) = view.pixel((a.0.max(b.0), a.1.max(b.1)));
+        if x0 >= self.width || y0 >= self.height {
+            return;
+        }
+        let (x1, y1) = (x1.min(self.width - 1), y1.min(self.height - 1));
+        for y in y0..=y1 {
+            self.pixels[y * self.width + x0..=y * self.width + x1].fill(color);
+        }
+    }
+
+    pub fn line(&mut self, from: (usize, usize), to: (usize, usize), color: Rgb) {
+        let (dx, dy) = (to.0 as f64 - from.0 as f64, to.1 as f64 - from.1 as f64);
+        let steps = dx.abs().max(dy.abs()).max(1.0);
+        for i in 0..=steps as usize {
+            let t = i as f64 / steps;
+            let x = (from.0 as f64 + dx * t).round() as usize;
+            let y = (from.1 as f64 + dy * t).round() as usize;
+            self.set(x, y, color);
+        }
+    }
+
+    // binary PPM (P6), readable by most image viewers and trivial to write
+    pub fn write_ppm(&self, mut out: impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.bytes())
+    }
+
+    pub fn write_png(&self, out: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.bytes())?;
+        Ok(writer.finish()?)
+    }
+
+    // PNG for a .png extension, PPM for anything else
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let out = BufWriter::new(fs::File::create(path)?);
+        if path.extension().is_some_and(|ext| ext == "png") {
+            self.write_png(out)
+        } else {
+            self.write_ppm(out)
+        }
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|&Rgb(r, g, b)| [r, g, b])
+            .collect()
+    }
+}
+
+enum Shape {
+    Polygon(Vec<Point2D>),
+    Rect(Point2D, Point2D),
+}
+
+// vector output for shapes in tile coordinates, each tile is a unit square centred on
+// its coordinate so outlines land on the tiles they pass through
+pub struct Svg {
+    shapes: Vec<(Shape, Option<Rgb>, Option<Rgb>)>,
+    max_side: usize,
+}
+
+impl Default for Svg {
+    fn default() -> Self {
+        Svg {
+            shapes: Vec::new(),
+            max_side: 1000,
+        }
+    }
+}
+
+impl Svg {
+    pub fn new() -> Self {
+        Svg::default()
+    }
+
+    // how many pixels the longer side is drawn at, the shapes are scaled to fit
+    pub fn max_side(mut self, max_side: usize) -> Self {
+        self.max_side = max_side;
+        self
+    }
+
+    pub fn polygon(mut self, points: &[Point2D], fill: Option<Rgb>, stroke: Option<Rgb>) -> Self {
+        self.shapes
+            .push((Shape::Polygon(points.to_vec()), fill, stroke));
+        self
+    }
+
+    // the box with `a` and `b` as opposite corners, both inclusive
+    pub fn rect(mut self, a: Point2D, b: Point2D, fill: Option<Rgb>, stroke: Option<Rgb>) -> Self {
+        let min = (a.0.min(b.0), a.1.min(b.1));
+        let max = (a.0.max(b.0), a.1.max(b.1));
+        self.shapes.push((Shape::Rect(min, max), fill, stroke));
+        self
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    fn bounds(&self) -> Option<(Point2D, Point2D)> {
+        bounds(self.shapes.iter().flat_map(|(shape, _, _)| match shape {
+            Shape::Polygon(points) => points.clone(),
+            Shape::Rect(min, max) => vec![*min, *max],
+        }))
+    }
+}
+
+impl fmt::Display for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ((min_x, min_y), (max_x, max_y)) = self.bounds().unwrap_or(((0, 0), (0, 0)));
+        let (span_x, span_y) = (max_x - min_x + 1, max_y - min_y + 1);
+        let scale = self.max_side as f64 / span_x.max(span_y) as f64;
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+            (span_x as f64 * scale).round(),
+            (span_y as f64 * scale).round(),
+            min_x as f64 - 0.5,
+            min_y as f64 - 0.5,
+            span_x,
+            span_y,
+        )?;
+        for (shape, fill, stroke) in &self.shapes {
+            match shape {
+                Shape::Polygon(points) => {
+                    let points = points.iter().map(|p| format!("{},{}", p.0, p.1));
+                    write!(
+                        f,
+                        r#"  <polygon points="{}""#,
+                        points.collect::<Vec<_>>().join(" ")
+                    )?;
+                }
+                Shape::Rect(min, max) => write!(
+                    f,
+                    r#"  <rect x="{}" y="{}" width="{}" height="{}""#,
+                    min.0 as f64 - 0.5,
+                    min.1 as f64 - 0.5,
+                    max.0 - min.0 + 1,
+                    max.1 - min.1 + 1,
+                )?,
+            }
+            match fill {
+                Some(fill) => write!(f, r#" fill="{}""#, fill)?,
+                None => write!(f, r#" fill="none""#)?,
+            }
+            if let Some(stroke) = stroke {
+                // keep outlines a pixel wide however far the view is scaled
+                write!(
+                    f,
+                    r#" stroke="{}" stroke-width="1" vector-effect="non-scaling-stroke""#,
+                    stroke
+                )?;
+            }
+            writeln!(f, "/>")?;
+        }
+        write!(f, "</svg>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the day 9 example loop
+    const LOOP: [Point2D; 8] = [
+        (7, 1),
+        (11, 1),
+        (11, 7),
+        (9, 7),
+        (9, 5),
+        (2, 5),
+        (2, 3),
+        (7, 3),
+    ];
+
+    #[test]
+    fn test_grid_to_ppm() {
+        let grid: Grid<char> = "#.\n.#".parse().unwrap();
+        let image = Image::from_grid(
+            &grid,
+            2,
+            |&c| if c == '#' { Rgb::WHITE } else { Rgb::BLACK },
+        );
+        assert_eq!((image.width(), image.height()), (4, 4));
+        assert_eq!(image.get(1, 1), Some(Rgb::WHITE));
+        assert_eq!(image.get(2, 1), Some(Rgb::BLACK));
+        assert_eq!(image.get(3, 3), Some(Rgb::WHITE));
+        assert_eq!(image.get(4, 0), None);
+
+        let mut ppm = Vec::new();
+        image.write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+    }
+
+    #[test]
+    fn test_png_signature() {
+        let mut png = Vec::new();
+        Image::new(3, 2, Rgb::RED).write_png(&mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+
+    #[test]
+    fn test_polygon_at_full_scale() {
+        let view = Viewport::fit(LOOP, 100).unwrap();
+        assert_eq!((view.width(), view.height()), (10, 7));
+        let mut image = Image::for_view(&view, Rgb::BLACK);
+        image.fill_polygon(&view, &LOOP, Rgb::GREEN);
+        image.stroke_polygon(&view, &LOOP, Rgb::RED);
+        // one pixel per tile, the pixel for tile (x, y) sits at (x - 2, y - 1)
+        assert_eq!(image.get(0, 2), Some(Rgb::RED));
+        assert_eq!(image.get(3, 3), Some(Rgb::GREEN));
+        assert_eq!(image.get(8, 5), Some(Rgb::GREEN));
+        assert_eq!(image.get(0, 0), Some(Rgb::BLACK));
+        assert_eq!(image.get(3, 5), Some(Rgb::BLACK));
+        let covered = image.pixels.iter().filter(|&&p| p != Rgb::BLACK).count();
+        assert_eq!(covered, 46);
+    }
+
+    #[test]
+    fn test_large_polygon_is_scaled_down() {
+        let square = [(0, 0), (99_999, 0), (99_999, 49_999), (0, 49_999)];
+        let view = Viewport::fit(square, 200).unwrap();
+        assert_eq!((view.width(), view.height()), (200, 100));
+        assert_eq!(view.pixel((99_999, 49_999)), (199, 99));
+        let mut image = Image::for_view(&view, Rgb::BLACK);
+        image.fill_polygon(&view, &square, Rgb::WHITE);
+        assert!(image.pixels.iter().all(|&p| p == Rgb::WHITE));
+    }
+
+    #[test]
+    fn test_rect_is_clipped_to_a_smaller_image() {
+        let view = Viewport::fit([(0, 0), (9, 9)], 100).unwrap();
+        let mut image = Image::new(4, 3, Rgb::BLACK);
+        image.fill_rect(&view, (2, 1), (9, 9), Rgb::BLUE);
+        assert_eq!(image.get(1, 1), Some(Rgb::BLACK));
+        assert_eq!(image.get(2, 1), Some(Rgb::BLUE));
+        assert_eq!(image.get(3, 2), Some(Rgb::BLUE));
+        image.fill_rect(&view, (5, 5), (9, 9), Rgb::RED);
+        assert!(!image.pixels.contains(&Rgb::RED));
+        Image::new(0, 0, Rgb::BLACK).fill_rect(&view, (0, 0), (9, 9), Rgb::RED);
+    }
+
+    #[test]
+    fn test_svg_shapes() {
+        let svg = Svg::new()
+            .max_side(100)
+            .polygon(&LOOP, Some(Rgb::GREEN), Some(Rgb::RED))
+            .rect((9, 5), (2, 3), None, Some(Rgb::BLUE))
+            .to_string();
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="70" viewBox="1.5 0.5 10 7">"#));
+        assert!(svg.contains(r##"<polygon points="7,1 11,1 11,7 9,7 9,5 2,5 2,3 7,3" fill="#3caa50" stroke="#dc2828""##));
+        assert!(svg.contains(r#"<rect x="1.5" y="2.5" width="8" height="3" fill="none""#));
+        assert!(svg.ends_with("</svg>"));
+    }
+}
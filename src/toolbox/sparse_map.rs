@@ -0,0 +1,126 @@
+use std::{collections::HashMap, hash::Hash};
+
+// the storage behind SparseGrid, SparseGrid3 and HexMap, only the cells that were set
+// are kept and every other key reads as `default`
+#[derive(Debug, Clone)]
+pub struct SparseMap<K, T> {
+    cells: HashMap<K, T>,
+    default: T,
+}
+
+impl<K: Copy + Eq + Hash, T> SparseMap<K, T> {
+    pub fn new(default: T) -> Self {
+        SparseMap {
+            cells: HashMap::new(),
+            default,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    // whether the cell was set, rather than falling back to the default
+    pub fn contains(&self, key: K) -> bool {
+        self.cells.contains_key(&key)
+    }
+
+    // what every unset cell reads as
+    pub fn default_cell(&self) -> &T {
+        &self.default
+    }
+
+    pub fn get(&self, key: K) -> &T {
+        self.cells.get(&key).unwrap_or(&self.default)
+    }
+
+    // the value only if the cell was set, for walks that mustn't step onto the default
+    pub fn occupied(&self, key: K) -> Option<&T> {
+        self.cells.get(&key)
+    }
+
+    // returns what was there before, if anything was
+    pub fn set(&mut self, key: K, value: T) -> Option<T> {
+        self.cells.insert(key, value)
+    }
+
+    pub fn remove(&mut self, key: K) -> Option<T> {
+        self.cells.remove(&key)
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    // occupied cells in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (K, &T)> {
+        self.cells.iter().map(|(&key, value)| (key, value))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
+        self.cells.keys().copied()
+    }
+}
+
+// the map's own PartialEq needs the bounds on K, which a derive wouldn't add
+impl<K: Eq + Hash, T: PartialEq> PartialEq for SparseMap<K, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.default == other.default && self.cells == other.cells
+    }
+}
+
+impl<K: Eq + Hash, T: Eq> Eq for SparseMap<K, T> {}
+
+impl<K: Copy + Eq + Hash, T: Default> Default for SparseMap<K, T> {
+    fn default() -> Self {
+        SparseMap::new(T::default())
+    }
+}
+
+impl<K: Copy + Eq + Hash, T: Default> FromIterator<(K, T)> for SparseMap<K, T> {
+    fn from_iter<I: IntoIterator<Item = (K, T)>>(iter: I) -> Self {
+        let mut map = SparseMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Copy + Eq + Hash, T> Extend<(K, T)> for SparseMap<K, T> {
+    fn extend<I: IntoIterator<Item = (K, T)>>(&mut self, iter: I) {
+        self.cells.extend(iter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unset_cells_read_as_the_default() {
+        let mut map = SparseMap::new('.');
+        assert_eq!(map.set((1, -2), '#'), None);
+        assert_eq!(map.set((1, -2), '@'), Some('#'));
+        assert_eq!(*map.get((1, -2)), '@');
+        assert_eq!(*map.get((0, 0)), '.');
+        assert!(map.contains((1, -2)) && !map.contains((0, 0)));
+        assert_eq!(map.occupied((0, 0)), None);
+        assert_eq!(map.remove((1, -2)), Some('@'));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_equality_includes_the_default() {
+        let a: SparseMap<u8, char> = [(1, 'a'), (2, 'b')].into_iter().collect();
+        let mut b = SparseMap::new('\0');
+        b.extend([(2, 'b'), (1, 'a')]);
+        assert_eq!(a, b);
+        assert_ne!(a, SparseMap::new('x'));
+        let mut keys: Vec<u8> = a.keys().collect();
+        keys.sort();
+        assert_eq!(keys, vec![1, 2]);
+    }
+}
@@ -0,0 +1,239 @@
+use std::str::FromStr;
+
+use crate::utils::parse_error::OffsetError;
+
+pub use aoc_derive::AocParse;
+
+// walks a line for the FromStr impls generated by #[derive(AocParse)],
+// each field takes the text up to the next literal of the pattern
+pub struct Cursor<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Cursor { text, pos: 0 }
+    }
+
+    pub fn literal(&mut self, literal: &str) -> Result<(), OffsetError> {
+        if self.rest().starts_with(literal) {
+            self.pos += literal.len();
+            Ok(())
+        } else {
+            Err(self.error_here(literal.len(), format!("`{}`", literal)))
+        }
+    }
+
+    pub fn end(&self) -> Result<(), OffsetError> {
+        if self.rest().is_empty() {
+            Ok(())
+        } else {
+            Err(self.error_here(self.rest().len(), "end of line"))
+        }
+    }
+
+    pub fn value<T: FromStr>(
+        &mut self,
+        until: Option<&str>,
+        field: &str,
+    ) -> Result<T, OffsetError> {
+        let (start, segment) = self.take_until(until)?;
+        segment
+            .parse()
+            .map_err(|_| OffsetError::new(start, segment.len(), describe::<T>(field)))
+    }
+
+    // `a,b,c` into any collection, a blank separator splits on runs of whitespace
+    pub fn list<C, T>(
+        &mut self,
+        until: Option<&str>,
+        sep: &str,
+        field: &str,
+    ) -> Result<C, OffsetError>
+    where
+        C: FromIterator<T>,
+        T: FromStr,
+    {
+        let (start, segment) = self.take_until(until)?;
+        parse_items(segment, start, sep, field)
+    }
+
+    // whitespace separated groups like `(1,3) (2) (2,3)`, each group a collection itself
+    pub fn groups<O, I, T>(
+        &mut self,
+        until: Option<&str>,
+        open: char,
+        sep: &str,
+        close: char,
+        field: &str,
+    ) -> Result<O, OffsetError>
+    where
+        O: FromIterator<I>,
+        I: FromIterator<T>,
+        T: FromStr,
+    {
+        let (start, segment) = self.take_until(until)?;
+        let mut groups = Vec::new();
+        let mut pos = 0;
+        loop {
+            let skipped = segment[pos..].len() - segment[pos..].trim_start().len();
+            pos += skipped;
+            if pos == segment.len() {
+                break;
+            }
+            if !segment[pos..].starts_with(open) {
+                return Err(OffsetError::new(start + pos, 1, format!("`{}`", open)));
+            }
+            let inner_start = pos + open.len_utf8();
+            let inner_len = segment[inner_start..].find(close).ok_or_else(|| {
+                OffsetError::new(start + pos, segment.len() - pos, format!("`{}`", close))
+            })?;
+            let inner = &segment[inner_start..inner_start + inner_len];
+            groups.push(parse_items(inner, start + inner_start, sep, field)?);
+            pos = inner_start + inner_len + close.len_utf8();
+        }
+        Ok(groups.into_iter().collect())
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn take_until(&mut self, until: Option<&str>) -> Result<(usize, &'a str), OffsetError> {
+        let rest = self.rest();
+        let len = match until {
+            Some(literal) => rest
+                .find(literal)
+                .ok_or_else(|| self.error_here(rest.len(), format!("`{}`", literal)))?,
+            None => rest.len(),
+        };
+        let start = self.pos;
+        self.pos += len;
+        Ok((start, &rest[..len]))
+    }
+
+    fn error_here(&self, width: usize, expected: impl Into<String>) -> OffsetError {
+        OffsetError::new(self.pos, width.min(self.rest().len()), expected)
+    }
+}
+
+fn parse_items<C, T>(segment: &str, start: usize, sep: &str, field: &str) -> Result<C, OffsetError>
+where
+    C: FromIterator<T>,
+    T: FromStr,
+{
+    let items: Vec<&str> = if sep.trim().is_empty() {
+        segment.split_whitespace().collect()
+    } else if segment.trim().is_empty() {
+        Vec::new()
+    } else {
+        segment.split(sep).map(str::trim).collect()
+    };
+    items
+        .into_iter()
+        .map(|item| {
+            item.parse().map_err(|_| {
+                let offset = item.as_ptr() as usize - segment.as_ptr() as usize;
+                OffsetError::new(start + offset, item.len(), describe::<T>(field))
+            })
+        })
+        .collect()
+}
+
+// "usize for `buttons`" reads better than the full type path
+fn describe<T>(field: &str) -> String {
+    let type_name = std::any::type_name::<T>();
+    let base = type_name.split('<').next().unwrap_or(type_name);
+    let short = base.rsplit("::").next().unwrap_or(base);
+    format!("{} for `{}`", short, field)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::utils::input::Input;
+
+    #[derive(Debug, PartialEq, AocParse)]
+    #[aoc(pattern = "[{lights}] {buttons:(,)*} {{{voltages:,}}}")]
+    struct Machine {
+        lights: String,
+        buttons: Vec<HashSet<usize>>,
+        voltages: Vec<u32>,
+    }
+
+    #[derive(Debug, PartialEq, AocParse)]
+    #[aoc(pattern = "{name}: {children: }")]
+    struct Device {
+        name: String,
+        children: Vec<String>,
+        #[aoc(skip)]
+        visited: bool,
+    }
+
+    #[derive(Debug, PartialEq, AocParse)]
+    #[aoc(pattern = "{width}x{height}: {counts: }")]
+    struct Region {
+        width: usize,
+        height: usize,
+        counts: Vec<usize>,
+    }
+
+    #[test]
+    fn test_machine_line() {
+        let machine: Machine = "[.##.] (3) (1,3) (2) (0,2) {3,5,4,7}".parse().unwrap();
+        assert_eq!(machine.lights, ".##.");
+        assert_eq!(machine.buttons.len(), 4);
+        assert_eq!(machine.buttons[1], HashSet::from([1, 3]));
+        assert_eq!(machine.voltages, vec![3, 5, 4, 7]);
+    }
+
+    #[test]
+    fn test_whitespace_list_and_skipped_field() {
+        let device: Device = "aaa: you hhh".parse().unwrap();
+        assert_eq!(
+            device,
+            Device {
+                name: "aaa".to_string(),
+                children: vec!["you".to_string(), "hhh".to_string()],
+                visited: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_region_header() {
+        let region: Region = "12x5: 1 0 1 0 2 2".parse().unwrap();
+        assert_eq!((region.width, region.height), (12, 5));
+        assert_eq!(region.counts, vec![1, 0, 1, 0, 2, 2]);
+    }
+
+    #[test]
+    fn test_bad_number_points_at_item() {
+        let error = "[.#] (1,x) {3}".parse::<Machine>().unwrap_err();
+        assert_eq!(error, OffsetError::new(8, 1, "usize for `buttons`"));
+    }
+
+    #[test]
+    fn test_missing_literal() {
+        let error = "[.#] (1) 3}".parse::<Machine>().unwrap_err();
+        assert_eq!(error.expected, "` {`");
+        assert_eq!(error.offset, 5);
+    }
+
+    #[test]
+    fn test_trailing_text() {
+        let error = "[.#] (1) {3} extra".parse::<Machine>().unwrap_err();
+        assert_eq!(error, OffsetError::new(12, 6, "end of line"));
+    }
+
+    #[test]
+    fn test_parse_lines_reports_line() {
+        let input = Input::new("4x4: 0 1\n12x5: 1 q").with_path("day12/input.txt");
+        let error = input.parse_lines::<Region>().unwrap_err();
+        assert_eq!((error.line, error.column), (2, 9));
+        assert_eq!(error.expected, "usize for `counts`");
+    }
+}
@@ -1,11 +1,159 @@
+use std::collections::HashMap;
+
+use crate::utils::{
+    input::Input,
+    input_crypto::{ENCRYPTED_SUFFIX, InputKey},
+};
+
 pub struct StdFileReader;
 
 pub trait FileReader {
-    fn read_file(&self, path: &str) -> Result<String, String>;
+    fn read_file(&self, path: &str) -> Result<Input, String>;
 }
 
 impl FileReader for StdFileReader {
-    fn read_file(&self, path: &str) -> Result<String, String> {
-        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))
+    fn read_file(&self, path: &str) -> Result<Input, String> {
+        std::fs::read_to_string(path)
+            .map(|raw| Input::new(&raw).with_path(path))
+            .map_err(|e| format!("Failed to read {}: {}", path, e))
+    }
+}
+
+// serves inputs straight from memory so tests can feed inline strings
+#[derive(Default)]
+pub struct MemoryFileReader {
+    files: HashMap<String, &'static str>,
+}
+
+impl MemoryFileReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file(mut self, path: &str, contents: &'static str) -> Self {
+        self.insert(path, contents);
+        self
+    }
+
+    pub fn insert(&mut self, path: &str, contents: &'static str) {
+        self.files.insert(path.to_string(), contents);
+    }
+}
+
+impl FileReader for MemoryFileReader {
+    fn read_file(&self, path: &str) -> Result<Input, String> {
+        self.files
+            .get(path)
+            .map(|contents| Input::new(contents).with_path(path))
+            .ok_or_else(|| format!("Failed to read {}: not found in memory", path))
+    }
+}
+
+#[cfg(feature = "embedded-inputs")]
+mod embedded {
+    include!(concat!(env!("OUT_DIR"), "/embedded_inputs.rs"));
+}
+
+// reads inputs that the build script baked into the binary, keyed by their repo path
+#[cfg(feature = "embedded-inputs")]
+pub struct EmbeddedFileReader;
+
+#[cfg(feature = "embedded-inputs")]
+impl FileReader for EmbeddedFileReader {
+    fn read_file(&self, path: &str) -> Result<Input, String> {
+        embedded::EMBEDDED_INPUTS
+            .iter()
+            .find(|(embedded_path, _)| *embedded_path == path)
+            .map(|(_, contents)| Input::new(contents).with_path(path))
+            .ok_or_else(|| format!("Failed to read {}: not embedded in this build", path))
+    }
+}
+
+// prefers the plaintext file, falls back to `<path>.enc` and decrypts it with the input key
+pub struct EncryptedFileReader<R: FileReader> {
+    inner: R,
+}
+
+impl<R: FileReader> EncryptedFileReader<R> {
+    pub fn new(inner: R) -> Self {
+        EncryptedFileReader { inner }
+    }
+}
+
+impl<R: FileReader> FileReader for EncryptedFileReader<R> {
+    fn read_file(&self, path: &str) -> Result<Input, String> {
+        self.inner.read_file(path).or_else(|plain_err| {
+            let encrypted_path = format!("{}{}", path, ENCRYPTED_SUFFIX);
+            match self.inner.read_file(&encrypted_path) {
+                Ok(encoded) => InputKey::load()?
+                    .decrypt(&encoded)
+                    .map(|plaintext| Input::new(&plaintext).with_path(path))
+                    .map_err(|e| format!("Failed to read {}: {}", encrypted_path, e)),
+                Err(_) => Err(plain_err),
+            }
+        })
+    }
+}
+
+// the reader the runner uses, depends on whether inputs are embedded
+#[cfg(feature = "embedded-inputs")]
+pub fn default_reader() -> EncryptedFileReader<EmbeddedFileReader> {
+    EncryptedFileReader::new(EmbeddedFileReader)
+}
+
+#[cfg(not(feature = "embedded-inputs"))]
+pub fn default_reader() -> EncryptedFileReader<StdFileReader> {
+    EncryptedFileReader::new(StdFileReader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_reader_returns_contents() {
+        let reader = MemoryFileReader::new().with_file("a.txt", "L68\nR48");
+        assert_eq!(
+            reader.read_file("a.txt"),
+            Ok(Input::new("L68\nR48").with_path("a.txt"))
+        );
+    }
+
+    #[test]
+    fn test_memory_reader_missing_path() {
+        let reader = MemoryFileReader::new();
+        assert!(reader.read_file("missing.txt").is_err());
+    }
+
+    #[test]
+    fn test_encrypted_reader_prefers_plaintext() {
+        let reader = EncryptedFileReader::new(
+            MemoryFileReader::new()
+                .with_file("input.txt", "plain")
+                .with_file("input.txt.enc", "not even hex"),
+        );
+        assert_eq!(
+            reader.read_file("input.txt"),
+            Ok(Input::new("plain").with_path("input.txt"))
+        );
+    }
+
+    #[test]
+    fn test_encrypted_reader_missing_both() {
+        let reader = EncryptedFileReader::new(MemoryFileReader::new());
+        assert_eq!(
+            reader.read_file("input.txt"),
+            Err("Failed to read input.txt: not found in memory".to_string())
+        );
+    }
+
+    #[cfg(feature = "embedded-inputs")]
+    #[test]
+    fn test_embedded_reader_matches_disk() {
+        let path = "src/years/year2025/day01/input.txt";
+        assert_eq!(
+            EmbeddedFileReader.read_file(path),
+            StdFileReader.read_file(path)
+        );
     }
 }
@@ -0,0 +1,327 @@
+use std::{fmt, ops::Deref, str::FromStr};
+
+use crate::{
+    toolbox::grid::{Grid, parse::Markers},
+    utils::parse_error::{OffsetError, ParseError},
+};
+
+// puzzle text with CRLF turned into LF and trailing blank lines dropped,
+// the contents of each line are left alone so column-aligned inputs keep their padding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    path: String,
+    text: String,
+}
+
+impl Input {
+    pub fn new(raw: &str) -> Self {
+        let text = raw.replace("\r\n", "\n");
+        let mut lines: Vec<&str> = text.split('\n').collect();
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+        Input {
+            path: String::new(),
+            text: lines.join("\n"),
+        }
+    }
+
+    // the path shows up in parse errors
+    pub fn with_path(mut self, path: &str) -> Self {
+        self.path = path.to_string();
+        self
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    // `at` should be a slice of this input, anything else is reported at the end of the input
+    pub fn error_at(&self, at: &str, expected: &str) -> ParseError {
+        self.error_at_offset(self.offset_of(at.as_bytes()), at.chars().count(), expected)
+    }
+
+    // the same for a slice of `as_bytes()`, a slice starting mid-char is reported at that char
+    pub fn error_at_bytes(&self, at: &[u8], expected: &str) -> ParseError {
+        let mut offset = self.offset_of(at);
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let width = String::from_utf8_lossy(at).chars().count();
+        self.error_at_offset(offset, width, expected)
+    }
+
+    fn offset_of(&self, at: &[u8]) -> usize {
+        (at.as_ptr() as usize)
+            .checked_sub(self.text.as_ptr() as usize)
+            .filter(|&offset| offset + at.len() <= self.text.len())
+            .unwrap_or(self.text.len())
+    }
+
+    pub fn error_at_offset(&self, offset: usize, width: usize, expected: &str) -> ParseError {
+        let line_start = self.text[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.text[offset..]
+            .find('\n')
+            .map_or(self.text.len(), |i| offset + i);
+        let snippet = &self.text[line_start..line_end];
+        ParseError {
+            path: self.path.clone(),
+            line: self.text[..line_start].matches('\n').count() + 1,
+            column: self.text[line_start..offset].chars().count() + 1,
+            width: width.min(snippet[offset - line_start..].chars().count()),
+            snippet: snippet.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+
+    pub fn parse_field<T: FromStr>(&self, field: &str, expected: &str) -> Result<T, ParseError> {
+        field.parse().map_err(|_| self.error_at(field, expected))
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.text.lines()
+    }
+
+    // groups of lines separated by one or more blank lines
+    pub fn blocks(&self) -> impl Iterator<Item = &str> {
+        let mut blocks = Vec::new();
+        let mut block_start: Option<usize> = None;
+        let mut block_end = 0;
+        let mut offset = 0;
+        for line in self.text.split('\n') {
+            if line.trim().is_empty() {
+                if let Some(start) = block_start.take() {
+                    blocks.push(&self.text[start..block_end]);
+                }
+            } else {
+                block_start.get_or_insert(offset);
+                block_end = offset + line.len();
+            }
+            offset += line.len() + 1;
+        }
+        if let Some(start) = block_start {
+            blocks.push(&self.text[start..block_end]);
+        }
+        blocks.into_iter()
+    }
+
+    // one cell per character, every line must be as wide as the first
+    pub fn grid<T: From<char>>(&self) -> Result<Grid<T>, ParseError> {
+        Grid::parse_with(&self.text, T::from).map_err(|e| self.locate(&self.text, e))
+    }
+
+    // a char grid plus where each of `markers` sits, e.g. `input.grid_with_markers("SE")`
+    pub fn grid_with_markers(&self, markers: &str) -> Result<(Grid<char>, Markers), ParseError> {
+        Grid::parse_with_markers(&self.text, markers, |c| c).map_err(|e| self.locate(&self.text, e))
+    }
+
+    // every integer in the text, a '-' only counts as a sign when it doesn't follow
+    // a digit or letter, so ranges like `3-5` give 3 and 5, fails at the first number
+    // that doesn't fit `T`, including negative ones when `T` is unsigned
+    pub fn ints<T: FromStr>(&self) -> Result<Vec<T>, ParseError> {
+        let bytes = self.text.as_bytes();
+        let mut numbers = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            if !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+            let mut start = i;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            if start > 0
+                && bytes[start - 1] == b'-'
+                && (start < 2 || !bytes[start - 2].is_ascii_alphanumeric())
+            {
+                start -= 1;
+            }
+            let number = self.text[start..i].parse().map_err(|_| {
+                let expected = format!("a number that fits in {}", std::any::type_name::<T>());
+                self.error_at_offset(start, i - start, &expected)
+            })?;
+            numbers.push(number);
+        }
+        Ok(numbers)
+    }
+
+    // turns an error from parsing `within` (a slice of this input) into one with line context
+    pub fn locate(&self, within: &str, error: OffsetError) -> ParseError {
+        let offset = (self.offset_of(within.as_bytes()) + error.offset).min(self.text.len());
+        self.error_at_offset(offset, error.width, &error.expected)
+    }
+
+    // one value per line for types whose FromStr reports an OffsetError, like #[derive(AocParse)]
+    pub fn parse_lines<T>(&self) -> Result<Vec<T>, ParseError>
+    where
+        T: FromStr<Err = OffsetError>,
+    {
+        self.lines()
+            .map(|line| line.parse().map_err(|e| self.locate(line, e)))
+            .collect()
+    }
+
+    pub fn split_once_each(&self, separator: &str) -> Result<Vec<(&str, &str)>, ParseError> {
+        self.lines()
+            .map(|line| {
+                line.split_once(separator)
+                    .ok_or_else(|| self.error_at(line, &format!("`{}`", separator)))
+            })
+            .collect()
+    }
+}
+
+impl From<String> for Input {
+    fn from(raw: String) -> Self {
+        Input::new(&raw)
+    }
+}
+
+impl From<&str> for Input {
+    fn from(raw: &str) -> Self {
+        Input::new(raw)
+    }
+}
+
+impl Deref for Input {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalizes_crlf_and_trailing_lines() {
+        let input = Input::new("L68\r\nR48\r\n\r\n  \n");
+        assert_eq!(input.as_str(), "L68\nR48");
+        assert_eq!(input.lines().collect::<Vec<_>>(), vec!["L68", "R48"]);
+    }
+
+    #[test]
+    fn test_keeps_column_padding() {
+        let input = Input::new("123 328\n 45 64 \n*   +  \n");
+        assert_eq!(
+            input.lines().collect::<Vec<_>>(),
+            vec!["123 328", " 45 64 ", "*   +  "]
+        );
+    }
+
+    #[test]
+    fn test_empty_input_has_no_lines() {
+        assert_eq!(Input::new("").lines().count(), 0);
+        assert_eq!(Input::new("\n\n").lines().count(), 0);
+        assert_eq!(Input::new("  \n").blocks().count(), 0);
+    }
+
+    #[test]
+    fn test_blocks() {
+        let input = Input::new("0:\r\n###\r\n\r\n1:\r\n#.#\r\n\r\n\r\n4x4: 0 1\r\n");
+        assert_eq!(
+            input.blocks().collect::<Vec<_>>(),
+            vec!["0:\n###", "1:\n#.#", "4x4: 0 1"]
+        );
+    }
+
+    #[test]
+    fn test_blocks_with_whitespace_separator() {
+        let input = Input::new("a\nb\n   \nc");
+        assert_eq!(input.blocks().collect::<Vec<_>>(), vec!["a\nb", "c"]);
+    }
+
+    #[test]
+    fn test_grid() {
+        let grid = Input::new("@..\n.@.\n").grid::<char>().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.cells(), &['@', '.', '.', '.', '@', '.']);
+        let error = Input::new("@..\n.@").grid::<char>().unwrap_err();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (2, "a row of width 3")
+        );
+    }
+
+    #[test]
+    fn test_ints() {
+        let input = Input::new("11-22,95-115\nx=-3, y=4\n162,817,812");
+        assert_eq!(
+            input.ints::<i64>(),
+            Ok(vec![11, 22, 95, 115, -3, 4, 162, 817, 812])
+        );
+        assert_eq!(Input::new("no numbers").ints::<u8>(), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_ints_reports_the_first_number_that_does_not_fit() {
+        let error = Input::new("11-22\nx=-3, y=4").ints::<u64>().unwrap_err();
+        assert_eq!((error.line, error.column, error.width), (2, 3, 2));
+        assert_eq!(error.expected, "a number that fits in u64");
+
+        let error = Input::new("200 300").ints::<u8>().unwrap_err();
+        assert_eq!((error.line, error.column, error.width), (1, 5, 3));
+    }
+
+    #[test]
+    fn test_split_once_each() {
+        let input = Input::new("aaa: you hhh\nyou: bbb ccc");
+        assert_eq!(
+            input.split_once_each(": "),
+            Ok(vec![("aaa", "you hhh"), ("you", "bbb ccc")])
+        );
+        assert!(Input::new("aaa you").split_once_each(": ").is_err());
+    }
+
+    #[test]
+    fn test_error_at_reports_line_and_column() {
+        let input = Input::new("11-22\n95-1x5").with_path("day02/input.txt");
+        let field = &input.lines().nth(1).unwrap()[3..];
+        let error = input.error_at(field, "range end");
+        assert_eq!(
+            (error.path.as_str(), error.line, error.column, error.width),
+            ("day02/input.txt", 2, 4, 3)
+        );
+        assert_eq!(error.snippet, "95-1x5");
+        assert_eq!(error.expected, "range end");
+    }
+
+    #[test]
+    fn test_error_at_empty_input() {
+        let input = Input::new("");
+        let error = input.error_at(&input, "range start");
+        assert_eq!(
+            (error.line, error.column, error.snippet.as_str()),
+            (1, 1, "")
+        );
+    }
+
+    #[test]
+    fn test_error_at_foreign_slice_points_at_end() {
+        let input = Input::new("ab\ncd");
+        let error = input.error_at("elsewhere", "something");
+        assert_eq!((error.line, error.column, error.width), (2, 3, 0));
+    }
+
+    #[test]
+    fn test_parse_field() {
+        let input = Input::new("7,x");
+        let (a, b) = input.split_once(',').unwrap();
+        assert_eq!(input.parse_field::<u32>(a, "a number"), Ok(7));
+        let error = input.parse_field::<u32>(b, "a number").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 3));
+    }
+}
@@ -0,0 +1,246 @@
+use std::path::PathBuf;
+
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng, rand_core::RngCore},
+};
+use sha2::Sha256;
+
+pub const KEY_ENV_VAR: &str = "AOC_INPUT_KEY";
+pub const KEY_FILE: &str = ".aoc-input-key";
+pub const ENCRYPTED_SUFFIX: &str = ".enc";
+
+// bumped whenever the layout after the header changes
+const FORMAT_VERSION: u8 = 1;
+// the version byte, then the kdf rounds as a big endian u32
+const HEADER_LEN: usize = 5;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+// pbkdf2-hmac-sha256 rounds, the owasp recommendation at the time of writing, tests
+// use far fewer so they don't spend seconds deriving keys, the count is stored in every
+// file so either build can read what the other wrote
+#[cfg(not(test))]
+const KDF_ROUNDS: u32 = 600_000;
+#[cfg(test)]
+const KDF_ROUNDS: u32 = 1_000;
+
+// holds the passphrase rather than a key, every file has its own salt so the key is
+// derived per file
+pub struct InputKey(String);
+
+impl InputKey {
+    pub fn from_passphrase(passphrase: &str) -> Self {
+        InputKey(passphrase.trim().to_string())
+    }
+
+    // env var wins over the key file so CI can inject a key without touching disk, the
+    // file is looked for in the working directory, next to the executable and then in
+    // the config dir
+    pub fn load() -> Result<Self, String> {
+        if let Ok(passphrase) = std::env::var(KEY_ENV_VAR) {
+            return Ok(Self::from_passphrase(&passphrase));
+        }
+        let candidates = key_file_candidates();
+        candidates
+            .iter()
+            .find_map(|path| std::fs::read_to_string(path).ok())
+            .map(|passphrase| Self::from_passphrase(&passphrase))
+            .ok_or_else(|| {
+                let paths: Vec<String> = candidates
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect();
+                format!(
+                    "No input key found, set {} or create {}",
+                    KEY_ENV_VAR,
+                    if paths.is_empty() {
+                        KEY_FILE.to_string()
+                    } else {
+                        paths.join(" or ")
+                    }
+                )
+            })
+    }
+
+    fn cipher(&self, salt: &[u8], rounds: u32) -> ChaCha20Poly1305 {
+        let mut key = Key::default();
+        pbkdf2::pbkdf2_hmac::<Sha256>(self.0.as_bytes(), salt, rounds, &mut key);
+        ChaCha20Poly1305::new(&key)
+    }
+
+    // output is hex text (header, salt, nonce, then ciphertext) so it survives any
+    // text-based reader
+    pub fn encrypt(&self, plaintext: &str) -> String {
+        self.encrypt_with_rounds(plaintext, KDF_ROUNDS)
+    }
+
+    fn encrypt_with_rounds(&self, plaintext: &str, rounds: u32) -> String {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher(&salt, rounds)
+            .encrypt(&nonce, plaintext.as_bytes())
+            .expect("encrypting in memory cannot fail");
+        let mut bytes = vec![FORMAT_VERSION];
+        bytes.extend(rounds.to_be_bytes());
+        bytes.extend(salt);
+        bytes.extend(nonce);
+        bytes.extend(ciphertext);
+        to_hex(&bytes)
+    }
+
+    pub fn decrypt(&self, encoded: &str) -> Result<String, String> {
+        let bytes = from_hex(encoded.trim())?;
+        if bytes.len() < HEADER_LEN + SALT_LEN + NONCE_LEN {
+            return Err("Encrypted input is truncated".to_string());
+        }
+        let (header, rest) = bytes.split_at(HEADER_LEN);
+        if header[0] != FORMAT_VERSION {
+            return Err(format!(
+                "Encrypted input has format version {}, expected {}",
+                header[0], FORMAT_VERSION
+            ));
+        }
+        let rounds = u32::from_be_bytes(header[1..].try_into().expect("header holds a u32"));
+        if rounds == 0 {
+            return Err("Encrypted input asks for 0 key derivation rounds".to_string());
+        }
+        let (salt, rest) = rest.split_at(SALT_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher(salt, rounds)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| "Failed to decrypt input, wrong key?".to_string())?;
+        String::from_utf8(plaintext).map_err(|e| format!("Decrypted input is not utf-8: {}", e))
+    }
+}
+
+// the working directory first, which is the repo root when running the solutions, then
+// next to the executable so a built binary carries its key around, then the platform
+// config dir which survives `cargo clean`
+fn key_file_candidates() -> Vec<PathBuf> {
+    let in_cwd = PathBuf::from(KEY_FILE);
+    let beside_exe = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(KEY_FILE)));
+    let in_config = config_dir().map(|dir| dir.join("aoc-rust").join("input-key"));
+    std::iter::once(in_cwd)
+        .chain(beside_exe)
+        .chain(in_config)
+        .collect()
+}
+
+fn config_dir() -> Option<PathBuf> {
+    let from_env = |var| {
+        std::env::var_os(var)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    if cfg!(windows) {
+        from_env("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        from_env("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        from_env("XDG_CONFIG_HOME").or_else(|| from_env("HOME").map(|home| home.join(".config")))
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> Result<Vec<u8>, String> {
+    if !s.is_ascii() {
+        return Err("Encrypted input is not hex".to_string());
+    }
+    if !s.len().is_multiple_of(2) {
+        return Err("Encrypted input has odd hex length".to_string());
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&s[i..i + 2], 16)
+                .map_err(|_| format!("Invalid hex in encrypted input at {}", i))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let key = InputKey::from_passphrase("hunter2");
+        let encrypted = key.encrypt("L68\nR48\n");
+        assert_ne!(encrypted, "L68\nR48\n");
+        assert_eq!(key.decrypt(&encrypted), Ok("L68\nR48\n".to_string()));
+    }
+
+    #[test]
+    fn test_wrong_key_fails() {
+        let encrypted = InputKey::from_passphrase("hunter2").encrypt("secret");
+        assert!(
+            InputKey::from_passphrase("hunter3")
+                .decrypt(&encrypted)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_passphrase_ignores_trailing_newline() {
+        let encrypted = InputKey::from_passphrase("hunter2\n").encrypt("secret");
+        assert_eq!(
+            InputKey::from_passphrase("hunter2").decrypt(&encrypted),
+            Ok("secret".to_string())
+        );
+    }
+
+    #[test]
+    fn test_every_file_gets_its_own_salt() {
+        let key = InputKey::from_passphrase("hunter2");
+        let (a, b) = (key.encrypt("secret"), key.encrypt("secret"));
+        let salt = HEADER_LEN * 2..(HEADER_LEN + SALT_LEN) * 2;
+        assert_ne!(a[salt.clone()], b[salt]);
+        assert_eq!(key.decrypt(&a), key.decrypt(&b));
+        // header, salt and nonce alone aren't enough
+        assert!(
+            key.decrypt(&a[..(HEADER_LEN + SALT_LEN + NONCE_LEN) * 2 - 2])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_rounds_are_read_from_the_file() {
+        let key = InputKey::from_passphrase("hunter2");
+        let encrypted = key.encrypt_with_rounds("secret", 1_500);
+        assert_eq!(&encrypted[..HEADER_LEN * 2], "01000005dc");
+        assert_eq!(key.decrypt(&encrypted), Ok("secret".to_string()));
+
+        let other_version = format!("02{}", &encrypted[2..]);
+        assert!(key.decrypt(&other_version).is_err());
+        let no_rounds = format!("0100000000{}", &encrypted[HEADER_LEN * 2..]);
+        assert!(key.decrypt(&no_rounds).is_err());
+    }
+
+    #[test]
+    fn test_key_file_locations() {
+        let exe_dir = std::env::current_exe()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .to_path_buf();
+        let candidates = key_file_candidates();
+        assert_eq!(candidates[0], PathBuf::from(KEY_FILE));
+        assert_eq!(candidates[1], exe_dir.join(KEY_FILE));
+    }
+
+    #[test]
+    fn test_rejects_bad_hex() {
+        let key = InputKey::from_passphrase("hunter2");
+        assert!(key.decrypt("zz").is_err());
+        assert!(key.decrypt("abc").is_err());
+        assert!(key.decrypt("aéb").is_err());
+    }
+}
@@ -2,6 +2,8 @@
 
 use std::cmp::{max, min};
 
+use crate::utils::point::Point3;
+
 pub type Point2D = (i128, i128);
 pub type Point3D = (i128, i128, i128);
 
@@ -13,7 +15,7 @@ pub fn find_divisors(v: &str) -> Vec<usize> {
     let sqrt_n = (n as f64).sqrt() as usize;
 
     for i in 1..=sqrt_n {
-        if n % i == 0 {
+        if n.is_multiple_of(i) {
             divisors.push(i);
             let pair = n / i;
             if pair != i {
@@ -27,10 +29,7 @@ pub fn find_divisors(v: &str) -> Vec<usize> {
 
 // just so I don't have to use f64
 pub fn calculate_euclidean_distance_squared(pos_1: Point3D, pos_2: Point3D) -> i128 {
-    let dx = pos_1.0 - pos_2.0;
-    let dy = pos_1.1 - pos_2.1;
-    let dz = pos_1.2 - pos_2.2;
-    dx * dx + dy * dy + dz * dz
+    Point3::from(pos_1).distance_squared(pos_2.into())
 }
 
 pub fn calculate_area(length: i128, width: i128) -> i128 {
@@ -113,13 +112,12 @@ pub fn get_rectangle_perimeter_lines(coord_1: Point2D, coord_2: Point2D) -> Vec<
     let min_y = min(coord_1.1, coord_2.1);
     let max_y: i128 = max(coord_1.1, coord_2.1);
 
-    let mut coordinates = Vec::new();
-    coordinates.push((min_x..=max_x).map(|x| (x, min_y)).collect());
-    coordinates.push((min_x..=max_x).map(|x| (x, max_y)).collect());
-    coordinates.push((min_y..=max_y).map(|y| (min_x, y)).collect());
-    coordinates.push((min_y..=max_y).map(|y| (max_x, y)).collect());
-
-    coordinates
+    vec![
+        (min_x..=max_x).map(|x| (x, min_y)).collect(),
+        (min_x..=max_x).map(|x| (x, max_y)).collect(),
+        (min_y..=max_y).map(|y| (min_x, y)).collect(),
+        (min_y..=max_y).map(|y| (max_x, y)).collect(),
+    ]
 }
 
 pub fn is_point_in_range(coord_1: Point2D, coord_2: Point2D, p: Point2D) -> bool {
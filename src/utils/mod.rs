@@ -1,4 +1,10 @@
+pub mod aoc_parse;
 pub mod file_reader;
+pub mod input;
+pub mod input_crypto;
 pub mod math_utils;
 pub mod memory_utils;
+pub mod parse;
+pub mod parse_error;
+pub mod point;
 pub mod str_utils;
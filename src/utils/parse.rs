@@ -0,0 +1,560 @@
+use std::str::FromStr;
+
+use crate::utils::{
+    input::Input,
+    parse_error::{OffsetError, ParseError},
+};
+
+// what the combinators run over, `&str` or `&[u8]`, bytes are taken one char each so
+// byte input is matched as ascii
+pub trait Text<'a>: Copy {
+    fn as_bytes(self) -> &'a [u8];
+
+    // in bytes, the longest prefix whose chars all pass `pred`
+    fn prefix_len(self, pred: impl Fn(char) -> bool) -> usize;
+
+    // in bytes, 0 when empty
+    fn first_char_len(self) -> usize;
+
+    fn split_at(self, mid: usize) -> (Self, Self);
+
+    fn to_str(self) -> Option<&'a str>;
+
+    // like `str::lines`, a trailing `\r` is dropped from each
+    fn lines(self) -> Vec<Self>;
+
+    fn chars(self) -> Vec<char>;
+
+    // `self` should be a slice of `input`
+    fn error_in(self, input: &Input, expected: &str) -> ParseError;
+
+    fn len(self) -> usize {
+        self.as_bytes().len()
+    }
+
+    fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
+    fn strip_prefix(self, prefix: &str) -> Option<Self> {
+        self.as_bytes()
+            .starts_with(prefix.as_bytes())
+            .then(|| self.split_at(prefix.len()).1)
+    }
+
+    fn starts_with(self, pred: impl Fn(char) -> bool) -> bool {
+        self.prefix_len(pred) > 0
+    }
+
+    // byte offset of the first `\n`
+    fn line_end(self) -> Option<usize> {
+        self.as_bytes().iter().position(|&b| b == b'\n')
+    }
+}
+
+impl<'a> Text<'a> for &'a str {
+    fn as_bytes(self) -> &'a [u8] {
+        str::as_bytes(self)
+    }
+
+    fn prefix_len(self, pred: impl Fn(char) -> bool) -> usize {
+        self.find(|c: char| !pred(c)).unwrap_or(str::len(self))
+    }
+
+    fn first_char_len(self) -> usize {
+        str::chars(self).next().map_or(0, char::len_utf8)
+    }
+
+    fn split_at(self, mid: usize) -> (Self, Self) {
+        str::split_at(self, mid)
+    }
+
+    fn to_str(self) -> Option<&'a str> {
+        Some(self)
+    }
+
+    fn lines(self) -> Vec<Self> {
+        str::lines(self).collect()
+    }
+
+    fn chars(self) -> Vec<char> {
+        str::chars(self).collect()
+    }
+
+    fn error_in(self, input: &Input, expected: &str) -> ParseError {
+        input.error_at(self, expected)
+    }
+}
+
+impl<'a> Text<'a> for &'a [u8] {
+    fn as_bytes(self) -> &'a [u8] {
+        self
+    }
+
+    fn prefix_len(self, pred: impl Fn(char) -> bool) -> usize {
+        self.iter()
+            .position(|&b| !pred(char::from(b)))
+            .unwrap_or(self.len())
+    }
+
+    fn first_char_len(self) -> usize {
+        usize::from(!self.is_empty())
+    }
+
+    fn split_at(self, mid: usize) -> (Self, Self) {
+        <[u8]>::split_at(self, mid)
+    }
+
+    fn to_str(self) -> Option<&'a str> {
+        std::str::from_utf8(self).ok()
+    }
+
+    fn lines(self) -> Vec<Self> {
+        let mut lines: Vec<Self> = self
+            .split(|&b| b == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .collect();
+        if self.is_empty() || self.ends_with(b"\n") {
+            lines.pop();
+        }
+        lines
+    }
+
+    fn chars(self) -> Vec<char> {
+        self.iter().map(|&b| char::from(b)).collect()
+    }
+
+    fn error_in(self, input: &Input, expected: &str) -> ParseError {
+        input.error_at_bytes(self, expected)
+    }
+}
+
+// where a parser gave up, `at` is the offending slice of the text being parsed so the
+// line and column fall out of pointer math against the Input instead of being threaded through
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure<I> {
+    pub at: I,
+    pub expected: String,
+}
+
+// the parsed value and whatever text is left after it
+pub type PResult<I, T> = Result<(T, I), Failure<I>>;
+
+fn fail<'a, I: Text<'a>, T>(rest: I, expected: impl Into<String>) -> PResult<I, T> {
+    Err(Failure {
+        at: token(rest),
+        expected: expected.into(),
+    })
+}
+
+// the word at the start of `s`, or just its first char, so carets cover the offending token
+fn token<'a, I: Text<'a>>(s: I) -> I {
+    let len = s.prefix_len(is_word);
+    s.split_at(if len > 0 { len } else { s.first_char_len() }).0
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn split_digits<'a, I: Text<'a>>(s: I) -> (I, I) {
+    s.split_at(s.prefix_len(|c| c.is_ascii_digit()))
+}
+
+pub fn uint<'a, T: FromStr, I: Text<'a>>(s: I) -> PResult<I, T> {
+    number(s, 0, "an unsigned integer")
+}
+
+pub fn int<'a, T: FromStr, I: Text<'a>>(s: I) -> PResult<I, T> {
+    let sign = usize::from(s.as_bytes().starts_with(b"-") || s.as_bytes().starts_with(b"+"));
+    number(s, sign, "an integer")
+}
+
+fn number<'a, T: FromStr, I: Text<'a>>(s: I, sign: usize, expected: &str) -> PResult<I, T> {
+    let (digits, rest) = split_digits(s.split_at(sign).1);
+    // a number glued to letters like `1x5` is one malformed token, not `1` followed by junk
+    if digits.is_empty() || rest.starts_with(is_word) {
+        let len = sign + token(s.split_at(sign).1).len();
+        return Err(Failure {
+            at: s.split_at(len).0,
+            expected: expected.to_string(),
+        });
+    }
+    let text = s.split_at(sign + digits.len()).0;
+    match text.to_str().and_then(|text| text.parse().ok()) {
+        Some(value) => Ok((value, rest)),
+        None => fail(text, expected),
+    }
+}
+
+// a name made of letters, digits and underscores
+pub fn word<'a, I: Text<'a>>(s: I) -> PResult<I, I> {
+    let len = s.prefix_len(is_word);
+    if len == 0 {
+        return fail(s, "a name");
+    }
+    Ok(s.split_at(len))
+}
+
+pub fn tag<'a, I: Text<'a>>(expected: &str) -> impl Fn(I) -> PResult<I, I> {
+    move |s| match s.strip_prefix(expected) {
+        Some(rest) => Ok((s.split_at(expected.len()).0, rest)),
+        None => fail(s, format!("`{}`", expected)),
+    }
+}
+
+// zero or more spaces or tabs, never fails
+pub fn spaces<'a, I: Text<'a>>(s: I) -> PResult<I, ()> {
+    Ok(((), s.split_at(s.prefix_len(|c| c == ' ' || c == '\t')).1))
+}
+
+pub fn opt<I: Copy, T>(parser: impl Fn(I) -> PResult<I, T>) -> impl Fn(I) -> PResult<I, Option<T>> {
+    move |s| match parser(s) {
+        Ok((value, rest)) => Ok((Some(value), rest)),
+        Err(_) => Ok((None, s)),
+    }
+}
+
+pub fn map<I, A, B>(
+    parser: impl Fn(I) -> PResult<I, A>,
+    f: impl Fn(A) -> B,
+) -> impl Fn(I) -> PResult<I, B> {
+    move |s| parser(s).map(|(value, rest)| (f(value), rest))
+}
+
+// swaps the expected message of a failure for something closer to the puzzle's wording
+pub fn expect<I, T>(
+    parser: impl Fn(I) -> PResult<I, T>,
+    expected: &str,
+) -> impl Fn(I) -> PResult<I, T> {
+    move |s| {
+        parser(s).map_err(|failure| Failure {
+            expected: expected.to_string(),
+            ..failure
+        })
+    }
+}
+
+pub fn pair<I, A, B>(
+    first: impl Fn(I) -> PResult<I, A>,
+    second: impl Fn(I) -> PResult<I, B>,
+) -> impl Fn(I) -> PResult<I, (A, B)> {
+    move |s| {
+        let (a, rest) = first(s)?;
+        let (b, rest) = second(rest)?;
+        Ok(((a, b), rest))
+    }
+}
+
+pub fn preceded<I, A, B>(
+    first: impl Fn(I) -> PResult<I, A>,
+    second: impl Fn(I) -> PResult<I, B>,
+) -> impl Fn(I) -> PResult<I, B> {
+    map(pair(first, second), |(_, b)| b)
+}
+
+pub fn terminated<I, A, B>(
+    first: impl Fn(I) -> PResult<I, A>,
+    second: impl Fn(I) -> PResult<I, B>,
+) -> impl Fn(I) -> PResult<I, A> {
+    map(pair(first, second), |(a, _)| a)
+}
+
+pub fn delimited<I, A, B, C>(
+    open: impl Fn(I) -> PResult<I, A>,
+    inner: impl Fn(I) -> PResult<I, B>,
+    close: impl Fn(I) -> PResult<I, C>,
+) -> impl Fn(I) -> PResult<I, B> {
+    preceded(open, terminated(inner, close))
+}
+
+// one or more items, an item is required after every separator
+pub fn separated<I: Copy, T, S>(
+    item: impl Fn(I) -> PResult<I, T>,
+    separator: impl Fn(I) -> PResult<I, S>,
+) -> impl Fn(I) -> PResult<I, Vec<T>> {
+    move |s| {
+        let (first, mut rest) = item(s)?;
+        let mut items = vec![first];
+        while let Ok((_, after_separator)) = separator(rest) {
+            let (next, after_item) = item(after_separator)?;
+            items.push(next);
+            rest = after_item;
+        }
+        Ok((items, rest))
+    }
+}
+
+// `a-b`, as in the id ranges of days 2 and 5
+pub fn range<'a, T: FromStr, I: Text<'a>>(s: I) -> PResult<I, (T, T)> {
+    pair(terminated(uint, tag("-")), uint)(s)
+}
+
+// `x,y`
+pub fn point2<'a, T: FromStr, I: Text<'a>>(s: I) -> PResult<I, (T, T)> {
+    pair(terminated(int, tag(",")), int)(s)
+}
+
+// `x,y,z`
+pub fn point3<'a, T: FromStr, I: Text<'a>>(s: I) -> PResult<I, (T, T, T)> {
+    map(
+        pair(
+            terminated(int, tag(",")),
+            pair(terminated(int, tag(",")), int),
+        ),
+        |(x, (y, z))| (x, y, z),
+    )(s)
+}
+
+// `name: a b c`
+pub fn adjacency<'a, I: Text<'a>>(s: I) -> PResult<I, (I, Vec<I>)> {
+    pair(terminated(word, tag(": ")), separated(word, tag(" ")))(s)
+}
+
+// `WxH`, digits on both sides of the `x` so `uint` can't swallow it
+pub fn dims<'a, I: Text<'a>>(s: I) -> PResult<I, (usize, usize)> {
+    let (width, rest) = split_digits(s);
+    let rest = rest
+        .strip_prefix("x")
+        .filter(|_| !width.is_empty())
+        .ok_or_else(|| Failure {
+            at: token(s),
+            expected: "dimensions like `4x4`".to_string(),
+        })?;
+    let (height, rest) = split_digits(rest);
+    let parse = |digits: I| digits.to_str().and_then(|digits| digits.parse().ok());
+    match (parse(width), parse(height)) {
+        (Some(width), Some(height)) => Ok(((width, height), rest)),
+        _ => fail(s, "dimensions like `4x4`"),
+    }
+}
+
+// `WxH: c0 c1 c2`, the region lines of day 12
+pub fn dims_header<'a, I: Text<'a>>(s: I) -> PResult<I, ((usize, usize), Vec<usize>)> {
+    pair(terminated(dims, tag(": ")), separated(uint, tag(" ")))(s)
+}
+
+// consecutive non-empty lines of equal width, stops before a blank line or the end of the text
+pub fn char_grid<'a, I: Text<'a>>(s: I) -> PResult<I, Vec<Vec<char>>> {
+    let mut rows: Vec<Vec<char>> = Vec::new();
+    let mut rest = s;
+    loop {
+        let (line, after) = rest.split_at(rest.line_end().unwrap_or(rest.len()));
+        if line.is_empty() {
+            break;
+        }
+        let row = line.chars();
+        if let Some(first) = rows.first()
+            && first.len() != row.len()
+        {
+            return Err(Failure {
+                at: line,
+                expected: format!("a row of width {}", first.len()),
+            });
+        }
+        rows.push(row);
+        rest = after;
+        match rest.strip_prefix("\n") {
+            Some(next) if !next.as_bytes().starts_with(b"\n") && !next.is_empty() => rest = next,
+            _ => break,
+        }
+    }
+    if rows.is_empty() {
+        return fail(s, "a grid of characters");
+    }
+    Ok((rows, rest))
+}
+
+fn complete<'a, I: Text<'a>, T>(
+    text: I,
+    parser: impl Fn(I) -> PResult<I, T>,
+) -> Result<T, Failure<I>> {
+    let (value, rest) = parser(text)?;
+    if rest.is_empty() {
+        Ok(value)
+    } else {
+        Err(Failure {
+            at: rest,
+            expected: "end of input".to_string(),
+        })
+    }
+}
+
+// runs `parser` over all of `text`, which should be a slice of `input` (or of its bytes)
+// so the caret lands right
+pub fn parse_all<'a, I: Text<'a>, T>(
+    input: &Input,
+    text: I,
+    parser: impl Fn(I) -> PResult<I, T>,
+) -> Result<T, ParseError> {
+    complete(text, parser).map_err(|failure| failure.at.error_in(input, &failure.expected))
+}
+
+pub fn parse_each_line<'a, I: Text<'a>, T>(
+    input: &Input,
+    text: I,
+    parser: impl Fn(I) -> PResult<I, T>,
+) -> Result<Vec<T>, ParseError> {
+    text.lines()
+        .into_iter()
+        .map(|line| parse_all(input, line, &parser))
+        .collect()
+}
+
+// for text that isn't part of an Input, the offset is in bytes from the start of `text`
+pub fn parse_str<'a, I: Text<'a>, T>(
+    text: I,
+    parser: impl Fn(I) -> PResult<I, T>,
+) -> Result<T, OffsetError> {
+    complete(text, parser).map_err(|failure| {
+        let offset = failure.at.as_bytes().as_ptr() as usize - text.as_bytes().as_ptr() as usize;
+        OffsetError::new(offset, failure.at.len(), failure.expected)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(uint::<u64, _>("123,4"), Ok((123, ",4")));
+        assert_eq!(int::<i64, _>("-17 x"), Ok((-17, " x")));
+        assert_eq!(int::<i64, _>("+3"), Ok((3, "")));
+        assert!(uint::<u64, _>("-1").is_err());
+        assert!(uint::<u8, _>("300").is_err());
+    }
+
+    #[test]
+    fn test_number_glued_to_letters_is_one_token() {
+        let failure = uint::<u64, _>("1x5,7").unwrap_err();
+        assert_eq!(failure.at, "1x5");
+    }
+
+    #[test]
+    fn test_separated_ranges() {
+        assert_eq!(
+            parse_str("11-22,95-115", separated(range::<u64, _>, tag(","))),
+            Ok(vec![(11, 22), (95, 115)])
+        );
+        assert_eq!(
+            parse_str("11-22,", separated(range::<u64, _>, tag(","))),
+            Err(OffsetError::new(6, 0, "an unsigned integer"))
+        );
+    }
+
+    #[test]
+    fn test_points() {
+        assert_eq!(parse_str("7,-1", point2::<i64, _>), Ok((7, -1)));
+        assert_eq!(
+            parse_str("162,817,812", point3::<i64, _>),
+            Ok((162, 817, 812))
+        );
+        assert_eq!(
+            parse_str("162,817", point3::<i64, _>),
+            Err(OffsetError::new(7, 0, "`,`"))
+        );
+    }
+
+    #[test]
+    fn test_adjacency() {
+        assert_eq!(
+            parse_str("aaa: you hhh", adjacency),
+            Ok(("aaa", vec!["you", "hhh"]))
+        );
+    }
+
+    #[test]
+    fn test_dims_header() {
+        assert_eq!(
+            parse_str("12x5: 1 0 1 0 2 2", dims_header),
+            Ok(((12, 5), vec![1, 0, 1, 0, 2, 2]))
+        );
+        assert_eq!(
+            parse_str("12: 1", dims_header),
+            Err(OffsetError::new(0, 2, "dimensions like `4x4`"))
+        );
+    }
+
+    #[test]
+    fn test_char_grid_stops_at_blank_line() {
+        let (grid, rest) = char_grid("#.\n.#\n\n0:").unwrap();
+        assert_eq!(grid, vec![vec!['#', '.'], vec!['.', '#']]);
+        assert_eq!(rest, "\n\n0:");
+    }
+
+    #[test]
+    fn test_char_grid_ragged_row() {
+        let failure = char_grid("###\n##\n###").unwrap_err();
+        assert_eq!(failure.at, "##");
+        assert_eq!(failure.expected, "a row of width 3");
+    }
+
+    #[test]
+    fn test_trailing_text() {
+        assert_eq!(
+            parse_str("3-5 x", range::<u64, _>),
+            Err(OffsetError::new(3, 2, "end of input"))
+        );
+    }
+
+    #[test]
+    fn test_parse_each_line_reports_position() {
+        let input = Input::new("1,2\n3,y").with_path("day09/input.txt");
+        let error = parse_each_line(&input, input.as_str(), point2::<i64, _>).unwrap_err();
+        assert_eq!((error.line, error.column, error.width), (2, 3, 1));
+        assert_eq!(error.expected, "an integer");
+    }
+
+    #[test]
+    fn test_bytes() {
+        let bytes: &[u8] = b"11-22,95-115";
+        assert_eq!(
+            parse_str(bytes, separated(range::<u64, _>, tag(","))),
+            Ok(vec![(11, 22), (95, 115)])
+        );
+        assert_eq!(
+            adjacency(&b"aaa: you hhh\n"[..]),
+            Ok(((&b"aaa"[..], vec![&b"you"[..], &b"hhh"[..]]), &b"\n"[..]))
+        );
+        let failure = point2::<i64, _>(&b"7,x1"[..]).unwrap_err();
+        assert_eq!(
+            (failure.at, failure.expected.as_str()),
+            (&b"x1"[..], "an integer")
+        );
+        let (grid, _) = char_grid(&b"#.\n.#"[..]).unwrap();
+        assert_eq!(grid, vec![vec!['#', '.'], vec!['.', '#']]);
+    }
+
+    #[test]
+    fn test_bytes_of_an_input_report_position() {
+        let input = Input::new("1,2\r\n3,y\r\n").with_path("day09/input.txt");
+        let error = parse_each_line(&input, input.as_bytes(), point2::<i64, _>).unwrap_err();
+        assert_eq!((error.line, error.column, error.width), (2, 3, 1));
+        // a failure inside a multi-byte char is reported at the start of that char
+        let input = Input::new("é");
+        let error = input.error_at_bytes(&input.as_bytes()[1..], "ascii");
+        assert_eq!((error.line, error.column), (1, 1));
+    }
+
+    #[test]
+    fn test_byte_lines() {
+        assert_eq!(Text::lines(&b"a\r\nb\n"[..]), vec![&b"a"[..], &b"b"[..]]);
+        assert_eq!(
+            Text::lines(&b"a\n\nb"[..]),
+            vec![&b"a"[..], &b""[..], &b"b"[..]]
+        );
+        assert!(Text::lines(&b""[..]).is_empty());
+    }
+
+    #[test]
+    fn test_expect_replaces_message() {
+        let input = Input::new("");
+        let error =
+            parse_all(&input, input.as_str(), expect(range::<u64, _>, "a range")).unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.expected.as_str()),
+            (1, 1, "a range")
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt;
+
+// points at the offending text of an input file, rendered rustc-style with carets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    pub width: usize,
+    pub snippet: String,
+    pub expected: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "expected {}", self.expected)?;
+        writeln!(
+            f,
+            "{}--> {}:{}:{}",
+            gutter, self.path, self.line, self.column
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.snippet)?;
+        write!(
+            f,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.width.max(1))
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// a failure inside a single string such as one line, `offset` is in bytes from its start and
+// `width` in chars, `Input::locate` turns it into a ParseError once the line is known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetError {
+    pub offset: usize,
+    pub width: usize,
+    pub expected: String,
+}
+
+impl OffsetError {
+    pub fn new(offset: usize, width: usize, expected: impl Into<String>) -> Self {
+        OffsetError {
+            offset,
+            width,
+            expected: expected.into(),
+        }
+    }
+}
+
+impl fmt::Display for OffsetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {} at offset {}", self.expected, self.offset)
+    }
+}
+
+impl std::error::Error for OffsetError {}
+
+// everything that can go wrong building a solver: the file couldn't be read or didn't parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputError {
+    Read(String),
+    Parse(ParseError),
+}
+
+impl From<String> for InputError {
+    fn from(message: String) -> Self {
+        InputError::Read(message)
+    }
+}
+
+impl From<ParseError> for InputError {
+    fn from(error: ParseError) -> Self {
+        InputError::Parse(error)
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Read(message) => write!(f, "{}", message),
+            InputError::Parse(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renders_caret_under_offending_text() {
+        let error = ParseError {
+            path: "src/years/year2025/day02/input.txt".to_string(),
+            line: 1,
+            column: 4,
+            width: 2,
+            snippet: "11-x2,95-115".to_string(),
+            expected: "range end".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "expected range end\n \
+             --> src/years/year2025/day02/input.txt:1:4\n  \
+             |\n\
+             1 | 11-x2,95-115\n  \
+             |    ^^"
+        );
+    }
+
+    #[test]
+    fn test_empty_span_still_gets_a_caret() {
+        let error = ParseError {
+            path: "input.txt".to_string(),
+            line: 12,
+            column: 1,
+            width: 0,
+            snippet: String::new(),
+            expected: "range start".to_string(),
+        };
+        assert!(error.to_string().ends_with("   | ^"));
+    }
+}
@@ -0,0 +1,410 @@
+use std::{
+    fmt,
+    hash::Hash,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+// signed integer coordinates, neighbours and rotations step below zero so unsigned
+// types are left out
+pub trait Coord:
+    Copy
+    + Ord
+    + Hash
+    + fmt::Debug
+    + fmt::Display
+    + Default
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs_diff(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+}
+
+macro_rules! coord {
+    ($($t:ty),*) => {
+        $(impl Coord for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+        })*
+    };
+}
+
+coord!(i32, i64, i128, isize);
+
+// x to the right and y downwards, the same way up as the grids
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Coord> Point2<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+
+    pub fn manhattan(&self, other: Self) -> T {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    // king moves, diagonals count as one step
+    pub fn chebyshev(&self, other: Self) -> T {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    pub fn distance_squared(&self, other: Self) -> T {
+        let d = *self - other;
+        d.dot(d)
+    }
+
+    pub fn dot(&self, other: Self) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    // z of the 3d cross product, positive when `other` is clockwise from self on screen
+    pub fn cross(&self, other: Self) -> T {
+        self.x * other.y - self.y * other.x
+    }
+
+    // up, right, down, left
+    pub fn neighbors4(&self) -> [Self; 4] {
+        let (zero, one) = (T::ZERO, T::ONE);
+        [
+            *self + Point2::new(zero, -one),
+            *self + Point2::new(one, zero),
+            *self + Point2::new(zero, one),
+            *self + Point2::new(-one, zero),
+        ]
+    }
+
+    // neighbors4 followed by the diagonals, clockwise from up-right
+    pub fn neighbors8(&self) -> [Self; 8] {
+        let one = T::ONE;
+        let [up, right, down, left] = self.neighbors4();
+        [
+            up,
+            right,
+            down,
+            left,
+            *self + Point2::new(one, -one),
+            *self + Point2::new(one, one),
+            *self + Point2::new(-one, one),
+            *self + Point2::new(-one, -one),
+        ]
+    }
+
+    // quarter turns about the origin as they look on screen
+    pub fn rotate_cw(&self) -> Self {
+        Point2::new(-self.y, self.x)
+    }
+
+    pub fn rotate_ccw(&self) -> Self {
+        Point2::new(self.y, -self.x)
+    }
+
+    // per axis, together they give the corners of a bounding box, named apart from
+    // Ord::min and Ord::max which compare whole points
+    pub fn min_components(&self, other: Self) -> Self {
+        Point2::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    pub fn max_components(&self, other: Self) -> Self {
+        Point2::new(self.x.max(other.x), self.y.max(other.y))
+    }
+}
+
+impl<T: Coord> Point3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+
+    pub fn manhattan(&self, other: Self) -> T {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    pub fn chebyshev(&self, other: Self) -> T {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+
+    pub fn distance_squared(&self, other: Self) -> T {
+        let d = *self - other;
+        d.dot(d)
+    }
+
+    pub fn dot(&self, other: Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: Self) -> Self {
+        Point3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    // the six cells sharing a face
+    pub fn neighbors6(&self) -> [Self; 6] {
+        let (zero, one) = (T::ZERO, T::ONE);
+        [
+            *self + Point3::new(-one, zero, zero),
+            *self + Point3::new(one, zero, zero),
+            *self + Point3::new(zero, -one, zero),
+            *self + Point3::new(zero, one, zero),
+            *self + Point3::new(zero, zero, -one),
+            *self + Point3::new(zero, zero, one),
+        ]
+    }
+
+    // quarter turns about each axis, right handed
+    pub fn rotate_x(&self) -> Self {
+        Point3::new(self.x, -self.z, self.y)
+    }
+
+    pub fn rotate_y(&self) -> Self {
+        Point3::new(self.z, self.y, -self.x)
+    }
+
+    pub fn rotate_z(&self) -> Self {
+        Point3::new(-self.y, self.x, self.z)
+    }
+
+    pub fn min_components(&self, other: Self) -> Self {
+        Point3::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    pub fn max_components(&self, other: Self) -> Self {
+        Point3::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+}
+
+// arithmetic is the same for both, one component at a time
+macro_rules! point_ops {
+    ($point:ident { $($field:ident),+ }) => {
+        impl<T: Coord> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                $point { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl<T: Coord> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                $point { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl<T: Coord> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, scalar: T) -> Self {
+                $point { $($field: self.$field * scalar),+ }
+            }
+        }
+
+        impl<T: Coord> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $point { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: Coord> AddAssign for $point<T> {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl<T: Coord> SubAssign for $point<T> {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+    };
+}
+
+point_ops!(Point2 { x, y });
+point_ops!(Point3 { x, y, z });
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(point: Point2<T>) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T> From<Point3<T>> for (T, T, T) {
+    fn from(point: Point3<T>) -> Self {
+        (point.x, point.y, point.z)
+    }
+}
+
+// the same comma separated form the puzzle inputs use
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::utils::math_utils::{Point2D, Point3D, calculate_euclidean_distance_squared};
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point2::new(3, -4);
+        let b = Point2::new(1, 2);
+        assert_eq!(a + b, Point2::new(4, -2));
+        assert_eq!(a - b, Point2::new(2, -6));
+        assert_eq!(a * 3, Point2::new(9, -12));
+        assert_eq!(-a, Point2::new(-3, 4));
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point2::new(0, 0);
+        let b = Point2::new(3, -4);
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(a.distance_squared(b), 25);
+        let p = Point3::new(162, 817, 812);
+        let q = Point3::new(425, 690, 689);
+        assert_eq!(
+            p.distance_squared(q),
+            calculate_euclidean_distance_squared(p.into(), q.into())
+        );
+        assert_eq!(p.manhattan(q), 263 + 127 + 123);
+    }
+
+    #[test]
+    fn test_products() {
+        assert_eq!(Point2::new(1, 0).dot(Point2::new(0, 1)), 0);
+        assert_eq!(Point2::new(1, 0).cross(Point2::new(0, 1)), 1);
+        let x = Point3::new(1, 0, 0);
+        let y = Point3::new(0, 1, 0);
+        assert_eq!(x.cross(y), Point3::new(0, 0, 1));
+        assert_eq!(Point3::new(1, 2, 3).dot(Point3::new(4, 5, 6)), 32);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let p = Point2::new(5i64, 5);
+        assert_eq!(p.neighbors4()[0], Point2::new(5, 4));
+        assert!(p.neighbors8().iter().all(|n| p.chebyshev(*n) == 1));
+        assert!(
+            Point3::new(0, 0, 0)
+                .neighbors6()
+                .iter()
+                .all(|n| n.manhattan(Point3::default()) == 1)
+        );
+    }
+
+    #[test]
+    fn test_rotations() {
+        // up turns to the right on screen
+        assert_eq!(Point2::new(0, -1).rotate_cw(), Point2::new(1, 0));
+        assert_eq!(Point2::new(1, 0).rotate_ccw(), Point2::new(0, -1));
+        assert_eq!(Point3::new(1, 0, 0).rotate_z(), Point3::new(0, 1, 0));
+        assert_eq!(Point3::new(0, 1, 0).rotate_x(), Point3::new(0, 0, 1));
+        assert_eq!(Point3::new(0, 0, 1).rotate_y(), Point3::new(1, 0, 0));
+    }
+
+    #[test]
+    fn test_tuple_conversions() {
+        let tuple: Point2D = (7, 1);
+        let point: Point2<i128> = tuple.into();
+        assert_eq!(point, Point2::new(7, 1));
+        assert_eq!(Point2D::from(point), tuple);
+        let tuple: Point3D = (1, 2, 3);
+        assert_eq!(Point3D::from(Point3::from(tuple)), tuple);
+        assert_eq!(Point3::new(1, -2, 3).to_string(), "1,-2,3");
+    }
+
+    #[test]
+    fn test_bounding_corners() {
+        let (a, b) = (Point2::new(1, 5), Point2::new(3, 2));
+        assert_eq!(a.min_components(b), Point2::new(1, 2));
+        assert_eq!(a.max_components(b), Point2::new(3, 5));
+        let (c, d) = (Point3::new(0, 9, -1), Point3::new(4, 2, 7));
+        assert_eq!(c.max_components(d), Point3::new(4, 9, 7));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_rotations_preserve_length(x in -1000i64..1000, y in -1000i64..1000) {
+            let p = Point2::new(x, y);
+            prop_assert_eq!(p.rotate_cw().distance_squared(Point2::default()), p.dot(p));
+            prop_assert_eq!(p.rotate_cw().rotate_ccw(), p);
+            prop_assert_eq!(p.rotate_cw().rotate_cw(), -p);
+            prop_assert_eq!(p.rotate_cw().dot(p), 0);
+        }
+
+        #[test]
+        fn prop_cross_is_orthogonal(
+            a in prop::array::uniform3(-100i64..100),
+            b in prop::array::uniform3(-100i64..100),
+        ) {
+            let (a, b) = (Point3::new(a[0], a[1], a[2]), Point3::new(b[0], b[1], b[2]));
+            let c = a.cross(b);
+            prop_assert_eq!(c.dot(a), 0);
+            prop_assert_eq!(c.dot(b), 0);
+            prop_assert_eq!(b.cross(a), -c);
+        }
+    }
+}
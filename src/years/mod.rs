@@ -1,10 +1,15 @@
+use crate::utils::parse_error::InputError;
+
 pub mod year2024;
 pub mod year2025;
 
-pub fn run_day(year: u32, day: u32) {
+pub fn run_day(year: u32, day: u32) -> Result<(), InputError> {
     match year {
         2024 => year2024::run_day(day),
         2025 => year2025::run_day(day),
-        _ => println!("Year {} not implemented", year),
+        _ => {
+            println!("Year {} not implemented", year);
+            Ok(())
+        }
     }
 }
@@ -1,11 +1,14 @@
-use crate::{solver::solver::Solver, utils::file_reader::FileReader};
+use crate::{
+    solver::solver::Solver,
+    utils::{file_reader::FileReader, input::Input, parse_error::InputError},
+};
 
 pub struct Day01 {
-    data: String,
+    data: Input,
 }
 
 impl Solver<u32> for Day01 {
-    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, String> {
+    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, InputError> {
         let data = reader.read_file(file_path)?;
         Ok(Day01 { data })
     }
@@ -1,15 +1,17 @@
-use crate::{solver::solver::Solver, utils::file_reader::StdFileReader};
+use crate::{
+    solver::solver::Solver,
+    utils::{file_reader::default_reader, parse_error::InputError},
+};
 
 pub mod day01;
 
-pub fn run_day(day: u32) {
-    let reader = StdFileReader;
+pub fn run_day(day: u32) -> Result<(), InputError> {
+    let reader = default_reader();
     match day {
-        1 => {
-            let mut solver =
-                day01::Day01::from_default_path(&reader, 2024, day).expect("Failed to load input");
-            solver.solve();
+        1 => day01::Day01::run(&reader, 2024, day),
+        _ => {
+            println!("Day {} not implemented", day);
+            Ok(())
         }
-        _ => println!("Day {} not implemented", day),
     }
 }
@@ -1,27 +1,70 @@
 use crate::{
-    solver::solver::Solver, toolbox::combination_safe::CombinationSafe,
-    utils::file_reader::FileReader,
+    solver::solver::Solver,
+    toolbox::combination_safe::{CombinationSafe, Dial},
+    utils::{file_reader::FileReader, parse_error::InputError},
 };
 
 pub struct Day01 {
-    data: String,
+    dials: Vec<Dial>,
 }
 
 impl Solver<u32> for Day01 {
-    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, String> {
+    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, InputError> {
         let data = reader.read_file(file_path)?;
-        Ok(Day01 { data })
+        let dials = data.parse_lines()?;
+        Ok(Day01 { dials })
     }
 
     fn part_one_solution(&mut self) -> u32 {
         let mut combination_safe: CombinationSafe = CombinationSafe::default();
-        let dials: Vec<&str> = self.data.split("\n").collect();
-        combination_safe.process_dials(&dials).0 as u32
+        combination_safe.process_dials(&self.dials).0 as u32
     }
 
     fn part_two_solution(&mut self) -> u32 {
         let mut combination_safe: CombinationSafe = CombinationSafe::default();
-        let dials: Vec<&str> = self.data.split("\n").collect();
-        (|(a, b)| (a + b) as u32)(combination_safe.process_dials(&dials))
+        let (landed, rotations) = combination_safe.process_dials(&self.dials);
+        (landed + rotations) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_dial() {
+        let mut solver = Day01::from_str("L50").expect("Failed to load input");
+        assert_eq!(solver.part_one_solution(), 1);
+        assert_eq!(solver.part_two_solution(), 1)
+    }
+
+    #[test]
+    fn test_trailing_newline() {
+        let mut solver = Day01::from_str("L50\r\nR100\r\n").expect("Failed to load input");
+        assert_eq!(solver.part_one_solution(), 2)
+    }
+
+    #[test]
+    fn test_passes_zero_without_landing() {
+        let mut solver = Day01::from_str("R60").expect("Failed to load input");
+        assert_eq!(solver.part_one_solution(), 0);
+        assert_eq!(solver.part_two_solution(), 1)
+    }
+
+    #[test]
+    fn test_bad_dial_is_a_parse_error() {
+        match Day01::from_str("L50\nX10\nR1x") {
+            Err(InputError::Parse(error)) => {
+                assert_eq!((error.line, error.column, error.width), (2, 1, 1));
+                assert_eq!(error.expected, "`L` or `R`");
+            }
+            _ => panic!("expected a parse error"),
+        }
+        match Day01::from_str("L50\nR1x") {
+            Err(InputError::Parse(error)) => {
+                assert_eq!((error.line, error.column, error.width), (2, 2, 2));
+            }
+            _ => panic!("expected a parse error"),
+        }
     }
 }
@@ -1,6 +1,11 @@
 use crate::{
     solver::solver::Solver,
-    utils::{file_reader::FileReader, math_utils::find_divisors},
+    utils::{
+        file_reader::FileReader,
+        math_utils::find_divisors,
+        parse::{expect, parse_all, range, separated, tag},
+        parse_error::InputError,
+    },
 };
 
 pub struct Day02 {
@@ -8,18 +13,13 @@ pub struct Day02 {
 }
 
 impl Solver<u64> for Day02 {
-    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, String> {
-        let data = reader.read_file(file_path)?;
-        let parsed = data
-            .split(',')
-            .map(|s| {
-                let mut parts = s.split('-');
-                (
-                    parts.next().unwrap().parse::<u64>().unwrap(),
-                    parts.next().unwrap().parse::<u64>().unwrap(),
-                )
-            })
-            .collect();
+    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, InputError> {
+        let input = reader.read_file(file_path)?;
+        let parsed = parse_all(
+            &input,
+            input.as_str(),
+            separated(expect(range, "a range like `11-22`"), tag(",")),
+        )?;
         Ok(Day02 { id_ranges: parsed })
     }
 
@@ -42,7 +42,7 @@ impl Solver<u64> for Day02 {
 
 fn is_invalid_part_one(n: u64) -> bool {
     let num_str = n.to_string();
-    num_str.len() % 2 == 0 && {
+    num_str.len().is_multiple_of(2) && {
         let (first, second) = num_str.split_at(num_str.len() / 2);
         first == second
     }
@@ -62,3 +62,30 @@ fn is_invalid_part_two(n: u64) -> bool {
                 .all(|i| &num_str[i..i + d] == first_segment)
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_input_is_a_parse_error() {
+        match Day02::from_str("") {
+            Err(InputError::Parse(error)) => {
+                assert_eq!((error.line, error.column), (1, 1));
+                assert_eq!(error.expected, "a range like `11-22`");
+            }
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn test_bad_range_end() {
+        match Day02::from_str("11-22,95-1x5") {
+            Err(InputError::Parse(error)) => {
+                assert_eq!((error.line, error.column, error.width), (1, 10, 3));
+                assert_eq!(error.snippet, "11-22,95-1x5");
+            }
+            _ => panic!("expected a parse error"),
+        }
+    }
+}
@@ -1,16 +1,30 @@
-use crate::{solver::solver::Solver, utils::file_reader::FileReader};
+use crate::{
+    solver::solver::Solver,
+    utils::{
+        file_reader::FileReader,
+        parse_error::{InputError, ParseError},
+    },
+};
 
 pub struct Day03 {
     data: Vec<Vec<u64>>,
 }
 
 impl Solver<u64> for Day03 {
-    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, String> {
-        let data = reader
-            .read_file(file_path)?
-            .split("\n")
-            .map(|x| x.chars().map(|c| c.to_digit(10).unwrap() as u64).collect())
-            .collect();
+    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, InputError> {
+        let input = reader.read_file(file_path)?;
+        let data = input
+            .lines()
+            .map(|line| {
+                line.char_indices()
+                    .map(|(i, c)| {
+                        c.to_digit(10)
+                            .map(u64::from)
+                            .ok_or_else(|| input.error_at(&line[i..i + c.len_utf8()], "a digit"))
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, ParseError>>()?;
         Ok(Day03 { data })
     }
 
@@ -61,5 +75,5 @@ fn find_largest_n_digit(bank: &[u64], n: usize) -> u64 {
     }
 
     let joined: String = result.iter().map(|d| d.to_string()).collect();
-    format!("{}", joined).parse::<u64>().unwrap()
+    joined.parse::<u64>().unwrap()
 }
@@ -1,86 +1,107 @@
 use crate::{
-    solver::solver::Solver,
-    toolbox::grid::{Direction, Grid},
-    utils::file_reader::FileReader,
+    solver::{options, solver::Solver},
+    toolbox::{
+        animate::Recorder,
+        automaton::Automaton,
+        grid::{Grid, Point, bits::BitGrid, display::Color, neighbors::Neighborhood},
+    },
+    utils::{file_reader::FileReader, parse_error::InputError},
 };
 
 pub struct Day04 {
-    grid: Grid<String>,
+    rolls: BitGrid,
+    frames: Option<Recorder>,
 }
 
 impl Solver<u32> for Day04 {
-    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, String> {
-        let data = reader.read_file(file_path)?;
-        let row_cols: Vec<Vec<String>> = data
-            .split("\n")
-            .map(|row| row.chars().map(|f| f.to_string()).collect())
-            .collect();
-
+    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, InputError> {
+        let grid = reader.read_file(file_path)?.grid::<char>()?;
         Ok(Day04 {
-            grid: Grid::<String> {
-                pos: (0, 0),
-                data: row_cols,
-            },
+            rolls: BitGrid::from_grid(&grid, |&c| c == '@'),
+            frames: None,
         })
     }
 
     fn part_one_solution(&mut self) -> u32 {
-        let flat: Vec<&String> = self.grid.flat();
-
-        self.grid
-            .flat()
-            .iter()
-            .enumerate()
-            .filter(|(idx, value)| {
-                value.as_str() == "@" && {
-                    let neighbor_count = Direction::all()
-                        .iter()
-                        .filter_map(|dir| self.grid.try_offset_index(*idx, dir)) // dir is a Direction
-                        .filter_map(|neighbor_idx| flat.get(neighbor_idx))
-                        .filter(|s| s.as_str() == "@")
-                        .count();
-                    neighbor_count < 4
-                }
-            })
-            .count() as u32
+        reachable(&self.rolls).count_ones() as u32
     }
 
     fn part_two_solution(&mut self) -> u32 {
-        let row_width = self.grid.data.len();
-        let total_len = self.grid.data[0].len() * row_width;
-        let mut total = 0;
-        let mut changed = true;
+        // a roll stays only while at least 4 others are around it
+        let rule =
+            |&roll: &bool, around: &[&bool]| roll && around.iter().filter(|&&&b| b).count() >= 4;
+        let mut automaton = Automaton::new(self.rolls.clone(), Neighborhood::Moore, rule);
+        let mut frames = Recorder::new("2025-day04");
+        if frames.is_recording() {
+            frames.push(picture(&self.rolls, automaton.world()));
+        }
+        while automaton.step() > 0 {
+            if frames.is_recording() {
+                frames.push(picture(&self.rolls, automaton.world()));
+            }
+        }
+        self.frames = Some(frames);
+        let rolls = automaton.into_world();
+        let removed = self.rolls.count_ones() - rolls.count_ones();
 
-        while changed {
-            changed = false;
-            let mut to_change = Vec::new();
+        if options::debug() {
+            let grid = picture(&self.rolls, &rolls);
+            let taken: Vec<Point> = grid.points().filter(|&p| grid[p] == 'x').collect();
+            eprintln!("{}\n", grid.overlay().highlight(&taken, Color::Yellow));
+        }
 
-            for idx in 0..total_len {
-                if let Some(cell) = self.grid.get_by_index(idx) {
-                    if cell.as_str() == "@" {
-                        let neighbor_count = Direction::all()
-                            .iter()
-                            .filter_map(|dir| self.grid.try_offset_index(idx, dir))
-                            .filter_map(|neighbor_idx| self.grid.get_by_index(neighbor_idx))
-                            .filter(|s| s.as_str() == "@")
-                            .count();
+        removed as u32
+    }
 
-                        if neighbor_count < 4 {
-                            to_change.push(idx);
-                        }
-                    }
-                }
-            }
+    fn take_frames(&mut self) -> Option<Recorder> {
+        self.frames.take()
+    }
+}
 
-            for idx in to_change {
-                if let Some(cell) = self.grid.get_mut_by_index(idx) {
-                    *cell = "x".to_string();
-                    total += 1;
-                    changed = true;
-                }
-            }
+// a roll of paper with fewer than 4 others around it can be reached and taken away
+fn reachable(rolls: &BitGrid) -> BitGrid {
+    let mut reachable = rolls.neighbor_counts(Neighborhood::Moore).fewer_than(4);
+    reachable.intersect_with(rolls);
+    reachable
+}
+
+// '@' for the rolls still there, 'x' for the ones taken away since `start`
+fn picture(start: &BitGrid, rolls: &BitGrid) -> Grid<char> {
+    Grid::from_fn(start.width(), start.height(), |p| {
+        match (start.get(p), rolls.get(p)) {
+            (_, true) => '@',
+            (true, false) => 'x',
+            _ => '.',
         }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@.@@
+@.@@@@..@.
+@@.@@@@.@@
+.@@@@@@@.@
+.@.@.@.@@@
+@.@@@.@@@@
+.@@@@@@@@.
+@.@.@@@.@.
+";
+
+    #[test]
+    fn test_part_one() {
+        let mut solver = Day04::from_str(EXAMPLE).expect("Failed to load input");
+        assert_eq!(solver.part_one_solution(), 13)
+    }
 
-        total
+    #[test]
+    fn test_part_two() {
+        let mut solver = Day04::from_str(EXAMPLE).expect("Failed to load input");
+        assert_eq!(solver.part_two_solution(), 43)
     }
 }
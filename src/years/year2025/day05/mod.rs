@@ -1,6 +1,13 @@
 use std::ops::RangeInclusive;
 
-use crate::{solver::solver::Solver, utils::file_reader::FileReader};
+use crate::{
+    solver::solver::Solver,
+    utils::{
+        file_reader::FileReader,
+        parse::{opt, pair, parse_all, preceded, tag, uint},
+        parse_error::InputError,
+    },
+};
 
 pub struct Day05 {
     ranges: Vec<RangeInclusive<u64>>,
@@ -8,20 +15,17 @@ pub struct Day05 {
 }
 
 impl Solver<u64> for Day05 {
-    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, String> {
+    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, InputError> {
         let data = reader.read_file(file_path)?;
-        let (ranges, ingredient_ids): (Vec<_>, Vec<_>) =
-            data.lines()
-                .fold((Vec::new(), Vec::new()), |(mut ranges, mut ids), s| {
-                    if let Some((start, end)) = s.split_once("-") {
-                        if let (Ok(start), Ok(end)) = (start.parse::<u64>(), end.parse::<u64>()) {
-                            ranges.push(start..=end);
-                        }
-                    } else if let Ok(id) = s.parse::<u64>() {
-                        ids.push(id);
-                    }
-                    (ranges, ids)
-                });
+        // `3-5` lines are fresh ranges, lone numbers are ingredient ids
+        let mut ranges = Vec::new();
+        let mut ingredient_ids = Vec::new();
+        for line in data.lines().filter(|line| !line.is_empty()) {
+            match parse_all(&data, line, pair(uint, opt(preceded(tag("-"), uint))))? {
+                (start, Some(end)) => ranges.push(start..=end),
+                (id, None) => ingredient_ids.push(id),
+            }
+        }
 
         Ok(Day05 {
             ranges,
@@ -32,26 +36,46 @@ impl Solver<u64> for Day05 {
     fn part_one_solution(&mut self) -> u64 {
         let mut fresh_ingredient_ids: Vec<u64> = Vec::new();
         for id in &self.ingredient_ids {
-            let in_range = self.ranges.iter().any(|range| range.contains(&id));
+            let in_range = self.ranges.iter().any(|range| range.contains(id));
             if in_range {
                 fresh_ingredient_ids.push(*id)
             }
         }
-        fresh_ingredient_ids.iter().count() as u64
+        fresh_ingredient_ids.len() as u64
     }
 
     fn part_two_solution(&mut self) -> u64 {
         self.ranges.sort_by_key(|r| *r.start());
         let mut merged: Vec<RangeInclusive<u64>> = Vec::new();
         for range in &self.ranges {
-            if let Some(last) = merged.last_mut() {
-                if *range.start() <= *last.end() + 1 {
-                    *last = *last.start()..=*range.end().max(last.end());
-                    continue;
-                }
+            if let Some(last) = merged.last_mut()
+                && *range.start() <= *last.end() + 1
+            {
+                *last = *last.start()..=*range.end().max(last.end());
+                continue;
             }
             merged.push(*range.start()..=*range.end());
         }
         merged.iter().map(|r| *r.end() - *r.start() + 1).sum()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_range_list() {
+        let mut solver = Day05::from_str("\n1\n5\n8").expect("Failed to load input");
+        assert_eq!(solver.part_one_solution(), 0);
+        assert_eq!(solver.part_two_solution(), 0)
+    }
+
+    #[test]
+    fn test_overlapping_ranges() {
+        let mut solver =
+            Day05::from_str("3-5\n4-8\n10-10\n\n4\n9\n10").expect("Failed to load input");
+        assert_eq!(solver.part_one_solution(), 2);
+        assert_eq!(solver.part_two_solution(), 7)
+    }
+}
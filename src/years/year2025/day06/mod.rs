@@ -1,140 +1,187 @@
-use std::collections::HashMap;
+use std::ops::Range;
 
-use crate::{solver::solver::Solver, utils::file_reader::FileReader};
+use crate::{
+    solver::solver::Solver,
+    toolbox::{
+        columns::{ReadOrder, Worksheet},
+        expr::{Expr, Op},
+    },
+    utils::{
+        file_reader::FileReader,
+        parse_error::{InputError, ParseError},
+    },
+};
 
 pub struct Day06 {
-    operation_order: Vec<char>,
-    num_sets: HashMap<usize, Vec<Vec<Option<char>>>>,
+    operation_order: Vec<Op>,
+    // each block's numbers read along its rows, then down its columns right to left
+    rows: Vec<Vec<u128>>,
+    columns: Vec<Vec<u128>>,
+}
+
+fn apply(op: Op, nums: &[u128]) -> u128 {
+    Expr::fold(op, nums.iter().copied()).map_or(0, |expr| {
+        expr.eval().unwrap_or_else(|e| panic!("{} in {}", e, expr))
+    })
+}
+
+// the part of `line` under a block, trimmed, `columns` count chars
+fn field(line: &str, columns: Range<usize>) -> &str {
+    let mut offsets = line.char_indices().map(|(i, _)| i).chain([line.len()]);
+    let start = offsets.nth(columns.start).unwrap_or(line.len());
+    let end = offsets
+        .nth(columns.len().saturating_sub(1))
+        .unwrap_or(line.len());
+    line[start..end].trim()
 }
 
 impl Solver<u128> for Day06 {
-    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, String> {
+    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, InputError> {
         let data = reader.read_file(file_path)?;
-        let mut lines: Vec<&str> = data.split("\n").collect();
-        let operation_order: Vec<char> = lines
+        let mut lines: Vec<&str> = data.lines().collect();
+        let operator_row = lines
             .pop()
-            .unwrap()
-            .split(" ")
-            .filter(|x| !x.is_empty())
-            .map(|x| x.trim().chars().next().unwrap())
-            .collect();
-        let mut formatted: Vec<Vec<String>> = Vec::new();
-        for line in &lines {
-            formatted.push(
-                line.split("")
-                    .filter(|x| x.len() > 0)
-                    .map(|x| {
-                        if x == " " {
-                            "NONE".to_string()
-                        } else {
-                            x.to_string()
-                        }
-                    })
-                    .collect::<Vec<String>>(),
-            );
+            .ok_or_else(|| data.error_at(&data, "a row of operators"))?;
+        if lines.is_empty() {
+            return Err(data
+                .error_at(&data, "rows of numbers above the operators")
+                .into());
         }
+        let num_sets = Worksheet::parse(lines.iter().copied()).blocks().to_vec();
+        // each operator sits under the first column of its block
+        let operation_order = num_sets
+            .iter()
+            .map(|block| {
+                field(operator_row, block.columns())
+                    .chars()
+                    .next()
+                    .and_then(Op::from_char)
+                    .ok_or_else(|| data.error_at(operator_row, "an operator under every column"))
+            })
+            .collect::<Result<_, _>>()?;
 
-        let num_positions = formatted[0].len();
-        let mut is_separator: Vec<bool> = vec![true; num_positions];
-        for row in &formatted {
-            for (i, ch) in row.iter().enumerate() {
-                if ch != "NONE" {
-                    is_separator[i] = false;
-                }
-            }
-        }
-
-        let mut num_sets: HashMap<usize, Vec<Vec<Option<char>>>> = HashMap::new();
-        let mut col_idx = 0;
-        let mut field_start = 0;
-        let mut in_field = false;
-
-        for i in 0..=num_positions {
-            let is_sep = if i < num_positions {
-                is_separator[i]
-            } else {
-                true
-            };
-
-            if !is_sep && !in_field {
-                field_start = i;
-                in_field = true;
-            } else if is_sep && in_field {
-                for row in &formatted {
-                    let field_chars: Vec<Option<char>> = row[field_start..i]
-                        .iter()
-                        .map(|s| if s == "NONE" { None } else { s.chars().next() })
-                        .collect();
-                    num_sets
-                        .entry(col_idx)
-                        .or_insert_with(Vec::new)
-                        .push(field_chars);
-                }
-                col_idx += 1;
-                in_field = false;
-            }
+        let mut rows = Vec::new();
+        let mut columns = Vec::new();
+        for block in &num_sets {
+            let row_numbers = block
+                .read_rows()
+                .iter()
+                .zip(&lines)
+                .map(|(value, line)| {
+                    value
+                        .parse()
+                        .map_err(|_| data.error_at(field(line, block.columns()), "a number"))
+                })
+                .collect::<Result<Vec<u128>, ParseError>>()?;
+            let column_numbers = block
+                .read_columns(ReadOrder::RightToLeft)
+                .iter()
+                .zip(block.columns().rev())
+                .map(|(value, col)| {
+                    value.parse().map_err(|_| {
+                        // the first character down the column that isn't a digit, or its top
+                        // one when every digit is fine but there are too many of them
+                        let cells: Vec<&str> =
+                            lines.iter().map(|line| field(line, col..col + 1)).collect();
+                        let at = cells
+                            .iter()
+                            .find(|cell| cell.chars().any(|c| !c.is_ascii_digit()))
+                            .or_else(|| cells.iter().find(|cell| !cell.is_empty()))
+                            .unwrap_or(&cells[0]);
+                        data.error_at(at, "digits in every column")
+                    })
+                })
+                .collect::<Result<Vec<u128>, ParseError>>()?;
+            rows.push(row_numbers);
+            columns.push(column_numbers);
         }
         Ok(Day06 {
             operation_order,
-            num_sets,
+            rows,
+            columns,
         })
     }
 
     fn part_one_solution(&mut self) -> u128 {
-        let mut total: u128 = 0;
-        for (i, symbol) in self.operation_order.iter().enumerate() {
-            let parsed_nums = self.num_sets[&i].iter().map(|x| {
-                x.iter()
-                    .filter_map(|opt| *opt)
-                    .collect::<String>()
-                    .parse::<u128>()
-                    .unwrap()
-            });
-            match symbol {
-                '*' => total += parsed_nums.product::<u128>(),
-                '+' => total += parsed_nums.sum::<u128>(),
-                _ => panic!("unhandled symbol: {}", symbol),
+        self.operation_order
+            .iter()
+            .zip(&self.rows)
+            .map(|(&op, numbers)| apply(op, numbers))
+            .sum()
+    }
+
+    fn part_two_solution(&mut self) -> u128 {
+        self.operation_order
+            .iter()
+            .zip(&self.columns)
+            .map(|(&op, numbers)| apply(op, numbers))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "123 328  51 64 \n 45 64  387 23 \n  6 98  215 314\n*   +   *   +  \n";
+
+    #[test]
+    fn test_part_one() {
+        let mut solver = Day06::from_str(EXAMPLE).expect("Failed to load input");
+        assert_eq!(solver.part_one_solution(), 4277556)
+    }
+
+    #[test]
+    fn test_part_two() {
+        let mut solver = Day06::from_str(EXAMPLE).expect("Failed to load input");
+        assert_eq!(solver.part_two_solution(), 3263827)
+    }
+
+    #[test]
+    fn test_unknown_operator_is_a_parse_error() {
+        match Day06::from_str("12 3\n 4 5\n*  ^") {
+            Err(InputError::Parse(error)) => {
+                assert_eq!(error.expected, "an operator under every column")
             }
+            _ => panic!("expected a parse error"),
         }
-        total
     }
 
-    fn part_two_solution(&mut self) -> u128 {
-        let mut total: u128 = 0;
-
-        for i in (0..self.operation_order.len()).rev() {
-            let symbol = self.operation_order[i];
-            let rows = &self.num_sets[&i];
-            let max_len = rows.iter().map(|row| row.len()).max().unwrap_or(0);
-
-            let transposed: Vec<Vec<Option<char>>> = (0..max_len)
-                .map(|digit_pos| {
-                    rows.iter()
-                        .map(|row| row.get(digit_pos).copied().flatten())
-                        .collect()
-                })
-                .collect();
+    #[test]
+    fn test_bad_number_is_a_parse_error() {
+        match Day06::from_str("12 3\n4x 5\n*  +") {
+            Err(InputError::Parse(error)) => {
+                assert_eq!((error.line, error.column, error.width), (2, 1, 2));
+                assert_eq!(error.expected, "a number")
+            }
+            _ => panic!("expected a parse error"),
+        }
+    }
 
-            let parsed_nums: Vec<u128> = transposed
-                .iter()
-                .rev()
-                .map(|digit_col| {
-                    digit_col
-                        .iter()
-                        .filter_map(|opt| *opt)
-                        .collect::<String>()
-                        .parse::<u128>()
-                        .unwrap_or(0)
-                })
-                .filter(|&n| n > 0)
-                .collect();
+    #[test]
+    fn test_block_wider_than_it_is_tall() {
+        let mut solver = Day06::from_str("1234 5\n  12 6\n*    +").expect("Failed to load input");
+        assert_eq!(solver.part_one_solution(), 1234 * 12 + 11);
+        // all four columns of the first block count, 42 * 31 * 2 * 1
+        assert_eq!(solver.part_two_solution(), 2604 + 56);
+    }
+
+    #[test]
+    fn test_operators_after_multibyte_characters() {
+        let mut solver = Day06::from_str("1 2\n3 4\n*é+").expect("Failed to load input");
+        assert_eq!(solver.part_one_solution(), 3 + 6);
+    }
 
-            match symbol {
-                '*' => total += parsed_nums.iter().product::<u128>(),
-                '+' => total += parsed_nums.iter().sum::<u128>(),
-                _ => panic!("unhandled symbol: {}", symbol),
+    #[test]
+    fn test_overflowing_column_points_at_it() {
+        // the right hand column reads 40 nines, too many for a u128
+        let input = format!("{}*", "19\n".repeat(40)).leak();
+        match Day06::from_str(input) {
+            Err(InputError::Parse(error)) => {
+                assert_eq!((error.line, error.column, error.width), (1, 2, 1));
+                assert_eq!(error.expected, "digits in every column")
             }
+            _ => panic!("expected a parse error"),
         }
-        total
     }
 }
@@ -1,39 +1,47 @@
-use crate::{solver::solver::Solver, utils::file_reader::FileReader};
+use crate::{
+    solver::{options, solver::Solver},
+    toolbox::{
+        animate::Recorder,
+        grid::{Point, display::Color},
+    },
+    utils::{file_reader::FileReader, parse_error::InputError},
+};
 use std::collections::{HashMap, HashSet, VecDeque};
 
 pub struct Day07 {
     caret_hits: u128,
     timelines: u128,
+    frames: Option<Recorder>,
 }
 
 impl Solver<u128> for Day07 {
-    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, String> {
-        let data = reader.read_file(file_path)?;
-        let grid: Vec<Vec<char>> = data.lines().map(|line| line.chars().collect()).collect();
-        let starting_pos = (
-            0 as usize,
-            grid[0].iter().position(|&x| x == 'S').unwrap() as usize,
-        );
+    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, InputError> {
+        let input = reader.read_file(file_path)?;
+        let (grid, markers) = input.grid_with_markers("S")?;
+        let start = markers
+            .one('S')
+            .ok_or_else(|| input.error_at(&input, "a start marker `S`"))?;
+        let starting_pos = (start.row, start.col);
         let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
         let mut caret_hits: HashSet<(usize, usize)> = HashSet::new();
         queue.push_back(starting_pos);
         while let Some((row, col)) = queue.pop_front() {
-            if row >= grid.len() || col >= grid[row].len() || caret_hits.contains(&(row, col)) {
+            if !grid.contains((row, col)) || caret_hits.contains(&(row, col)) {
                 continue;
             }
 
-            match grid[row][col] {
+            match grid[(row, col)] {
                 '^' => {
                     caret_hits.insert((row, col));
                     if col > 0 {
                         queue.push_back((row, col - 1));
                     }
-                    if col + 1 < grid[row].len() {
+                    if col + 1 < grid.width() {
                         queue.push_back((row, col + 1));
                     }
                 }
                 '.' | 'S' => {
-                    if row + 1 < grid.len() {
+                    if row + 1 < grid.height() {
                         queue.push_back((row + 1, col));
                     }
                 }
@@ -41,11 +49,33 @@ impl Solver<u128> for Day07 {
             }
         }
 
+        if options::debug() {
+            let hits: Vec<Point> = caret_hits.iter().map(|&hit| hit.into()).collect();
+            eprintln!(
+                "{}\n",
+                grid.overlay()
+                    .highlight(&hits, Color::Red)
+                    .colored_label(start, 'S', Color::Green)
+            );
+        }
+
         // because bfs is fighting me so much
         let mut path_counts: HashMap<usize, u128> = HashMap::new();
         path_counts.insert(starting_pos.1, 1);
 
-        for row in &grid {
+        // the beams drawn in as they move down, one row per frame
+        let mut frames = Recorder::new("2025-day07");
+        let mut beams = grid.clone();
+
+        for (row_index, row) in grid.rows().enumerate().skip(start.row) {
+            if frames.is_recording() {
+                for &col in path_counts.keys() {
+                    if beams[(row_index, col)] == '.' {
+                        beams[(row_index, col)] = '|';
+                    }
+                }
+                frames.push(&beams);
+            }
             let mut new_counts: HashMap<usize, u128> = HashMap::new();
 
             for (&col, &count) in &path_counts {
@@ -71,6 +101,7 @@ impl Solver<u128> for Day07 {
         Ok(Day07 {
             caret_hits: caret_hits.len() as u128,
             timelines: path_counts.values().copied().sum::<u128>(),
+            frames: Some(frames),
         })
     }
 
@@ -81,4 +112,8 @@ impl Solver<u128> for Day07 {
     fn part_two_solution(&mut self) -> u128 {
         self.timelines
     }
+
+    fn take_frames(&mut self) -> Option<Recorder> {
+        self.frames.take()
+    }
 }
@@ -1,13 +1,19 @@
-use std::collections::HashSet;
+use std::cmp::Reverse;
 
 use crate::{
-    solver::solver::Solver,
+    solver::{options, solver::Solver},
+    toolbox::{
+        grid::sparse::SparseGrid,
+        render::{self, Image, Rgb, Svg, Viewport},
+    },
     utils::{
         file_reader::FileReader,
         math_utils::{
             Point2D, calculate_area, get_rectangle_perimeter_lines, x_axis_diff_inclusive,
             y_axis_diff_inclusive,
         },
+        parse::{parse_each_line, point2},
+        parse_error::InputError,
     },
 };
 use itertools::Itertools;
@@ -18,30 +24,56 @@ pub struct Day09 {
     max_area_within_green_tiles: i128,
 }
 
-#[derive(Debug)]
-enum Color {
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Tile {
+    #[default]
+    Empty,
     Red,
     Green,
 }
 
-type Tile = (Point2D, Color);
+impl From<Tile> for char {
+    fn from(tile: Tile) -> char {
+        match tile {
+            Tile::Empty => '.',
+            Tile::Red => '#',
+            Tile::Green => 'X',
+        }
+    }
+}
+
+// the loop of tiles with the biggest rectangle that fits inside it, as an SVG and a PNG
+fn render_tiles(red_points: &[Point2D], best: Option<(Point2D, Point2D)>) -> std::io::Result<()> {
+    let Some(view) = Viewport::fit(red_points.iter().copied(), 1000) else {
+        return Ok(());
+    };
+    let mut svg = Svg::new().polygon(red_points, Some(Rgb::GREEN), Some(Rgb::RED));
+    let mut image = Image::for_view(&view, Rgb::WHITE);
+    image.fill_polygon(&view, red_points, Rgb::GREEN);
+    if let Some((a, b)) = best {
+        svg = svg.rect(a, b, None, Some(Rgb::BLUE));
+        image.fill_rect(&view, a, b, Rgb::BLUE);
+    }
+    image.stroke_polygon(&view, red_points, Rgb::RED);
+
+    if let Some(path) = render::output("2025-day09.svg") {
+        svg.save(path?)?;
+    }
+    if let Some(path) = render::output("2025-day09.png") {
+        image.save(path?)?;
+    }
+    Ok(())
+}
 
 impl Solver<i128> for Day09 {
-    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, String> {
-        let data = reader.read_file(file_path)?;
+    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, InputError> {
+        let input = reader.read_file(file_path)?;
         // 2d grid
-        let mut tiles: Vec<Tile> = data
-            .split("\n")
-            .map(|x| {
-                let slice: Vec<i128> = x.split(",").map(|y| y.parse::<i128>().unwrap()).collect();
-                ((slice[0], slice[1]), Color::Red)
-            })
-            .collect();
-
-        let red_points: Vec<Point2D> = tiles.iter().map(|t| t.0).collect();
+        let red_points: Vec<Point2D> = parse_each_line(&input, input.as_str(), point2)?;
+        let mut tiles: SparseGrid<Tile> =
+            red_points.iter().map(|&point| (point, Tile::Red)).collect();
 
         // Generate green boundary tiles
-        let mut green_tiles: Vec<Tile> = Vec::new();
         for i in 0..red_points.len() {
             let a = red_points[i];
             let b = red_points[(i + 1) % red_points.len()];
@@ -53,15 +85,22 @@ impl Solver<i128> for Day09 {
 
             if a.1 == b.1 {
                 for x in (min_x + 1)..max_x {
-                    green_tiles.push(((x, a.1), Color::Green));
+                    tiles.set((x, a.1), Tile::Green);
                 }
             } else if a.0 == b.0 {
                 for y in (min_y + 1)..max_y {
-                    green_tiles.push(((a.0, y), Color::Green));
+                    tiles.set((a.0, y), Tile::Green);
                 }
             }
         }
-        tiles.extend(green_tiles);
+        // the real input spans ~100k cells a side, only small examples are worth drawing
+        if options::debug()
+            && tiles
+                .bounds()
+                .is_some_and(|(min, max)| max.0 - min.0 < 200 && max.1 - min.1 < 200)
+        {
+            eprintln!("{}\n", tiles);
+        }
 
         // Build vertical edges for ray casting
         let mut vertical_edges: Vec<(i128, i128, i128)> = Vec::new(); // (x, y_min, y_max)
@@ -87,14 +126,13 @@ impl Solver<i128> for Day09 {
             })
             .collect();
 
-        red_pairs.sort_by(|a, b| b.2.cmp(&a.2));
+        red_pairs.sort_by_key(|pair| Reverse(pair.2));
 
         let max_area = red_pairs.first().map(|(_, _, area)| *area).unwrap_or(0);
 
-        let boundary_points: HashSet<Point2D> = tiles.iter().map(|t| t.0).collect();
         // ray casting
         let is_inside_polygon = |point: Point2D| -> bool {
-            if boundary_points.contains(&point) {
+            if tiles.contains(point) {
                 return true; // On boundary = inside
             }
             let crossings = vertical_edges
@@ -126,18 +164,20 @@ impl Solver<i128> for Day09 {
                 };
 
                 // first valid
-                for idx in start..end {
-                    if check_valid_idx(idx) {
-                        return Some(idx);
-                    }
-                }
-                None
+                (start..end).find(|&idx| check_valid_idx(idx))
             })
             .collect();
 
         let first_valid_idx = chunk_results.into_iter().flatten().min();
         let max_area_within_green_tiles = first_valid_idx.map(|idx| red_pairs[idx].2).unwrap_or(0);
 
+        if options::render_dir().is_some() {
+            let best = first_valid_idx.map(|idx| (red_pairs[idx].0, red_pairs[idx].1));
+            if let Err(err) = render_tiles(&red_points, best) {
+                eprintln!("Failed to render day 9: {}", err);
+            }
+        }
+
         Ok(Day09 {
             max_area,
             max_area_within_green_tiles,
@@ -153,45 +193,21 @@ impl Solver<i128> for Day09 {
     }
 }
 
-// fn print_tiles(tiles: &[Tile], padding: i128) {
-//     use std::collections::HashMap;
-
-//     if tiles.is_empty() {
-//         println!("No tiles to display");
-//         return;
-//     }
-
-//     let raw_min_x = tiles.iter().map(|t| t.0.0).min().unwrap();
-//     let raw_min_y = tiles.iter().map(|t| t.0.1).min().unwrap();
-
-//     // Don't go below 0
-//     let min_x = (raw_min_x - padding).max(0);
-//     let max_x = tiles.iter().map(|t| t.0.0).max().unwrap() + padding;
-//     let min_y = (raw_min_y - padding).max(0);
-//     let max_y = tiles.iter().map(|t| t.0.1).max().unwrap() + padding;
-
-//     println!(
-//         "Bounds: x={}..{}, y={}..{}, tile count={}",
-//         min_x,
-//         max_x,
-//         min_y,
-//         max_y,
-//         tiles.len()
-//     );
-
-//     let tile_map: HashMap<Point2D, &Color> = tiles.iter().map(|t| (t.0, &t.1)).collect();
-
-//     for y in min_y..=max_y {
-//         for x in min_x..=max_x {
-//             let point = (x, y);
-//             let ch = match tile_map.get(&point) {
-//                 Some(Color::Red) => '#',
-//                 Some(Color::Green) => 'X',
-//                 None => '.',
-//             };
-//             print!("{}", ch);
-//         }
-//         println!(); // Add marker at end
-//     }
-//     println!();
-// }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "7,1\n11,1\n11,7\n9,7\n9,5\n2,5\n2,3\n7,3\n";
+
+    #[test]
+    fn test_part_one() {
+        let mut solver = Day09::from_str(EXAMPLE).expect("Failed to load input");
+        assert_eq!(solver.part_one_solution(), 50)
+    }
+
+    #[test]
+    fn test_part_two() {
+        let mut solver = Day09::from_str(EXAMPLE).expect("Failed to load input");
+        assert_eq!(solver.part_two_solution(), 24)
+    }
+}
@@ -1,20 +1,18 @@
 use good_lp::{Expression, Solution, SolverModel, default_solver, variable, variables};
 use std::collections::HashSet;
 
-use crate::{solver::solver::Solver, utils::file_reader::FileReader};
+use crate::{
+    solver::solver::Solver,
+    utils::{aoc_parse::AocParse, file_reader::FileReader, parse_error::InputError},
+};
 use itertools::Itertools;
-use once_cell::sync::Lazy;
-use regex::Regex;
 
-static LIGHT_IND_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[([^\]]+)\]").unwrap());
-static BUTTON_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\(([^)]+)\)").unwrap());
-static VOLTAGE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{([^}]+)\}").unwrap());
-
-type Switch = Vec<char>;
+type Switch = String;
 type Button = HashSet<usize>;
 type Voltage = Vec<usize>;
 
-#[derive(Debug)]
+#[derive(Debug, AocParse)]
+#[aoc(pattern = "[{light_ind}] {buttons:(,)*} {{{voltages:,}}}")]
 struct SwitchState {
     light_ind: Switch,
     buttons: Vec<Button>,
@@ -24,9 +22,9 @@ struct SwitchState {
 impl SwitchState {
     fn calculate_target_set(&self) -> HashSet<usize> {
         self.light_ind
-            .iter()
+            .chars()
             .enumerate()
-            .filter(|&(_, &ch)| ch == '#') // Only positions that should be 'on'
+            .filter(|&(_, ch)| ch == '#') // Only positions that should be 'on'
             .map(|(i, _)| i)
             .collect()
     }
@@ -44,38 +42,14 @@ fn xor_multiple_sets(sets: &[&HashSet<usize>]) -> HashSet<usize> {
     result
 }
 
-fn parse_switch_state(line: &str) -> SwitchState {
-    let parse_usize_list =
-        |s: &str| -> Vec<usize> { s.split(",").map(|x| x.trim().parse().unwrap()).collect() };
-
-    SwitchState {
-        light_ind: LIGHT_IND_RE
-            .captures(line)
-            .map(|caps| caps[1].chars().collect())
-            .unwrap_or_default(),
-        buttons: BUTTON_RE
-            .captures_iter(line)
-            .map(|caps| {
-                parse_usize_list(&caps[1])
-                    .into_iter()
-                    .collect::<HashSet<usize>>()
-            })
-            .collect(),
-        voltages: VOLTAGE_RE
-            .captures(line)
-            .map(|caps| parse_usize_list(&caps[1]))
-            .unwrap_or_default(),
-    }
-}
-
 pub struct Day10 {
     configurations: Vec<SwitchState>,
 }
 
 impl Solver<i32> for Day10 {
-    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, String> {
+    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, InputError> {
         let data = reader.read_file(file_path)?;
-        let configurations: Vec<SwitchState> = data.split("\n").map(parse_switch_state).collect();
+        let configurations = data.parse_lines::<SwitchState>()?;
         Ok(Day10 { configurations })
     }
 
@@ -121,7 +95,7 @@ impl Solver<i32> for Day10 {
                 let mut expr = Expression::default();
                 for (button_idx, button) in config.buttons.iter().enumerate() {
                     if button.contains(&voltage_idx) {
-                        expr = expr + button_counts[button_idx];
+                        expr += button_counts[button_idx];
                     }
                 }
                 model = model.with(expr.eq(target as f64));
@@ -1,33 +1,27 @@
 use std::collections::HashMap;
 
-use crate::{solver::solver::Solver, utils::file_reader::FileReader};
+use crate::{
+    solver::solver::Solver,
+    utils::{aoc_parse::AocParse, file_reader::FileReader, parse_error::InputError},
+};
 
 pub struct Day11 {
     data: Vec<Node>,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, AocParse)]
+#[aoc(pattern = "{name}: {children: }")]
 struct Node {
     name: String,
     children: Vec<String>,
+    #[aoc(skip)] // will be set later
     parent: Option<Box<Node>>,
 }
 
 impl Solver<i64> for Day11 {
-    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, String> {
+    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, InputError> {
         let data = reader.read_file(file_path)?;
-        let nodes: Vec<Node> = data
-            .lines()
-            .map(|line| {
-                let (name, children) = line.split_once(": ").unwrap();
-                let children = children.split(" ").map(|c| c.to_string()).collect();
-                Node {
-                    name: name.to_string(),
-                    children,
-                    parent: None, // will be set later
-                }
-            })
-            .collect();
+        let nodes = data.parse_lines::<Node>()?;
 
         let mut nodes_clone = nodes.clone();
         for node in nodes_clone.iter_mut() {
@@ -62,7 +56,7 @@ fn count_paths_dfs(data: &Vec<Node>, current: &Node, target: &str) -> i64 {
 
     for child_name in &current.children {
         if let Some(child_node) = data.iter().find(|n| n.name == *child_name) {
-            count += count_paths_dfs(&data, child_node, target);
+            count += count_paths_dfs(data, child_node, target);
         }
     }
 
@@ -4,12 +4,18 @@ use rayon::prelude::*;
 
 use crate::{
     solver::solver::Solver,
+    toolbox::grid::{
+        Point,
+        bits::{BitGrid, BitMask},
+    },
     utils::{
         file_reader::FileReader,
         math_utils::{
             reflect_grid_points_horizontal, reflect_grid_points_vertical, rotate_grid_points,
             sort_coords,
         },
+        parse::{char_grid, dims_header, pair, parse_all, parse_each_line, tag, terminated, uint},
+        parse_error::InputError,
     },
 };
 
@@ -49,26 +55,27 @@ fn generate_all_shape_variants(points: &[(usize, usize)]) -> Vec<Vec<(usize, usi
     variants
 }
 
-type Placement = Vec<usize>;
-
 fn precompute_placements(
     width: usize,
     height: usize,
     variants: &[Vec<(usize, usize)>],
-) -> Vec<Placement> {
+) -> Vec<BitMask> {
+    let board = BitGrid::new(width, height);
     let mut placements = Vec::new();
 
     for variant in variants {
         let max_row = variant.iter().map(|&(r, _)| r).max().unwrap_or(0);
         let max_col = variant.iter().map(|&(_, c)| c).max().unwrap_or(0);
+        if max_row >= height || max_col >= width {
+            continue;
+        }
 
-        for start_row in 0..=height.saturating_sub(max_row + 1) {
-            for start_col in 0..=width.saturating_sub(max_col + 1) {
-                let cells: Placement = variant
+        for start_row in 0..height - max_row {
+            for start_col in 0..width - max_col {
+                let cells = variant
                     .iter()
-                    .map(|&(r, c)| (start_row + r) * width + (start_col + c))
-                    .collect();
-                placements.push(cells);
+                    .map(|&(r, c)| Point::new(start_row + r, start_col + c));
+                placements.push(board.mask_of(cells));
             }
         }
     }
@@ -76,46 +83,46 @@ fn precompute_placements(
     placements
 }
 
-fn can_pack_shapes(width: usize, height: usize, all_variants: &[Vec<Vec<(usize, usize)>>]) -> bool {
-    let mut all_placements: Vec<(usize, Vec<Placement>)> = all_variants
+fn can_pack_shapes(
+    width: usize,
+    height: usize,
+    shape_ids: &[usize],
+    variants: &HashMap<usize, Vec<Vec<(usize, usize)>>>,
+) -> bool {
+    // copies of a shape share one set of placements
+    let mut placements: HashMap<usize, Vec<BitMask>> = HashMap::new();
+    for &id in shape_ids {
+        placements
+            .entry(id)
+            .or_insert_with(|| precompute_placements(width, height, &variants[&id]));
+    }
+
+    let mut all_placements: Vec<&[BitMask]> = shape_ids
         .iter()
-        .enumerate()
-        .map(|(idx, variants)| (idx, precompute_placements(width, height, variants)))
+        .map(|id| placements[id].as_slice())
         .collect();
 
-    all_placements.sort_by_key(|(_, placements)| placements.len());
+    all_placements.sort_by_key(|placements| placements.len());
 
-    let sorted_placements: Vec<Vec<Placement>> =
-        all_placements.into_iter().map(|(_, p)| p).collect();
+    let mut occupied = BitGrid::new(width, height);
 
-    let grid_size = width * height;
-    let mut occupied = vec![false; grid_size];
-
-    backtrack_pack(&mut occupied, &sorted_placements, 0)
+    backtrack_pack(&mut occupied, &all_placements, 0)
 }
 
-fn backtrack_pack(
-    occupied: &mut Vec<bool>,
-    all_placements: &[Vec<Placement>],
-    shape_idx: usize,
-) -> bool {
+fn backtrack_pack(occupied: &mut BitGrid, all_placements: &[&[BitMask]], shape_idx: usize) -> bool {
     if shape_idx >= all_placements.len() {
         return true;
     }
 
-    for placement in &all_placements[shape_idx] {
-        if placement.iter().all(|&cell| !occupied[cell]) {
-            for &cell in placement {
-                occupied[cell] = true;
-            }
+    for placement in all_placements[shape_idx] {
+        if !occupied.overlaps(placement) {
+            occupied.set_mask(placement);
 
             if backtrack_pack(occupied, all_placements, shape_idx + 1) {
                 return true;
             }
 
-            for &cell in placement {
-                occupied[cell] = false;
-            }
+            occupied.clear_mask(placement);
         }
     }
 
@@ -123,22 +130,21 @@ fn backtrack_pack(
 }
 
 impl Solver<u32> for Day12 {
-    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, String> {
+    fn new<R: FileReader>(reader: &R, file_path: &str) -> Result<Self, InputError> {
         let data = reader.read_file(file_path)?;
-        let shape_blocks: Vec<&str> = data.split("\n\n").collect();
+        let shape_blocks: Vec<&str> = data.blocks().collect();
 
         let mut shapes: Shapes = HashMap::new();
         let mut regions: Regions = Vec::new();
 
         for block in shape_blocks {
             if block.contains(":") && !block.contains("x") {
-                let mut lines = block.lines();
-                let header = lines.next().unwrap();
-                let shape_id: usize = header.trim_end_matches(":").parse::<usize>().unwrap();
+                let (shape_id, rows): (usize, _) =
+                    parse_all(&data, block, pair(terminated(uint, tag(":\n")), char_grid))?;
 
                 let mut coords = Vec::new();
-                for (row, line) in lines.enumerate() {
-                    for (col, ch) in line.chars().enumerate() {
+                for (row, line) in rows.iter().enumerate() {
+                    for (col, &ch) in line.iter().enumerate() {
                         if ch == '#' {
                             coords.push((row, col));
                         }
@@ -155,28 +161,15 @@ impl Solver<u32> for Day12 {
                     shapes.entry(shape_id).insert_entry(normalized);
                 }
             } else {
-                for line in block.lines() {
-                    if let Some((dims, shape_counts_str)) = line.split_once(": ") {
-                        let (width, height) = dims
-                            .split_once('x')
-                            .ok_or_else(|| format!("Invalid dimensions: {}", dims))?;
-                        let width: usize = width.parse::<usize>().unwrap();
-                        let height: usize = height.parse::<usize>().unwrap();
-
-                        let shape_counts: Vec<usize> = shape_counts_str
-                            .split_whitespace()
-                            .map(|s| s.parse::<usize>().unwrap())
-                            .collect();
-
-                        let mut shape_list = Vec::new();
-                        for (shape_id, &count) in shape_counts.iter().enumerate() {
-                            for _ in 0..count {
-                                shape_list.push(shape_id);
-                            }
+                for ((width, height), shape_counts) in parse_each_line(&data, block, dims_header)? {
+                    let mut shape_list = Vec::new();
+                    for (shape_id, &count) in shape_counts.iter().enumerate() {
+                        for _ in 0..count {
+                            shape_list.push(shape_id);
                         }
-
-                        regions.push(((width, height), shape_list));
                     }
+
+                    regions.push(((width, height), shape_list));
                 }
             }
         }
@@ -185,31 +178,27 @@ impl Solver<u32> for Day12 {
     }
 
     fn part_one_solution(&mut self) -> u32 {
+        let variants: HashMap<usize, Vec<Vec<(usize, usize)>>> = self
+            .shapes
+            .iter()
+            .map(|(&id, shape)| (id, generate_all_shape_variants(shape)))
+            .collect();
+
         self.regions
             .par_iter()
             .filter(|((grid_w, grid_h), shape_indices)| {
-                let shape_list: Vec<Vec<(usize, usize)>> = shape_indices
-                    .iter()
-                    .filter_map(|&id| self.shapes.get(&id).cloned())
-                    .collect();
-
-                if shape_list.len() != shape_indices.len() {
+                if shape_indices.iter().any(|id| !self.shapes.contains_key(id)) {
                     return false;
                 }
 
-                let total_area: usize = shape_list.iter().map(|s| s.len()).sum();
+                let total_area: usize = shape_indices.iter().map(|id| self.shapes[id].len()).sum();
                 let grid_area = grid_w * grid_h;
 
                 if total_area > grid_area {
                     return false;
                 }
 
-                let all_variants: Vec<Vec<Vec<(usize, usize)>>> = shape_list
-                    .iter()
-                    .map(|s| generate_all_shape_variants(s))
-                    .collect();
-
-                can_pack_shapes(*grid_w, *grid_h, &all_variants)
+                can_pack_shapes(*grid_w, *grid_h, shape_indices, &variants)
             })
             .count() as u32
     }
@@ -1,4 +1,7 @@
-use crate::{solver::solver::Solver, utils::file_reader::StdFileReader};
+use crate::{
+    solver::solver::Solver,
+    utils::{file_reader::default_reader, parse_error::InputError},
+};
 
 pub mod day01;
 pub mod day02;
@@ -13,69 +16,24 @@ pub mod day10;
 pub mod day11;
 pub mod day12;
 
-pub fn run_day(day: u32) {
-    let reader = StdFileReader;
+pub fn run_day(day: u32) -> Result<(), InputError> {
+    let reader = default_reader();
     match day {
-        1 => {
-            let mut solver =
-                day01::Day01::from_default_path(&reader, 2025, day).expect("Failed to load input");
-            solver.solve();
+        1 => day01::Day01::run(&reader, 2025, day),
+        2 => day02::Day02::run(&reader, 2025, day),
+        3 => day03::Day03::run(&reader, 2025, day),
+        4 => day04::Day04::run(&reader, 2025, day),
+        5 => day05::Day05::run(&reader, 2025, day),
+        6 => day06::Day06::run(&reader, 2025, day),
+        7 => day07::Day07::run(&reader, 2025, day),
+        8 => day08::Day08::run(&reader, 2025, day),
+        9 => day09::Day09::run(&reader, 2025, day),
+        10 => day10::Day10::run(&reader, 2025, day),
+        11 => day11::Day11::run(&reader, 2025, day),
+        12 => day12::Day12::run(&reader, 2025, day),
+        _ => {
+            println!("Day {} not implemented", day);
+            Ok(())
         }
-        2 => {
-            let mut solver =
-                day02::Day02::from_default_path(&reader, 2025, day).expect("Failed to load input");
-            solver.solve();
-        }
-        3 => {
-            let mut solver =
-                day03::Day03::from_default_path(&reader, 2025, day).expect("Failed to load input");
-            solver.solve();
-        }
-        4 => {
-            let mut solver =
-                day04::Day04::from_default_path(&reader, 2025, day).expect("Failed to load input");
-            solver.solve();
-        }
-        5 => {
-            let mut solver =
-                day05::Day05::from_default_path(&reader, 2025, day).expect("Failed to load input");
-            solver.solve();
-        }
-        6 => {
-            let mut solver =
-                day06::Day06::from_default_path(&reader, 2025, day).expect("Failed to load input");
-            solver.solve();
-        }
-        7 => {
-            let mut solver =
-                day07::Day07::from_default_path(&reader, 2025, day).expect("Failed to load input");
-            solver.solve();
-        }
-        8 => {
-            let mut solver =
-                day08::Day08::from_default_path(&reader, 2025, day).expect("Failed to load input");
-            solver.solve();
-        }
-        9 => {
-            let mut solver =
-                day09::Day09::from_default_path(&reader, 2025, day).expect("Failed to load input");
-            solver.solve();
-        }
-        10 => {
-            let mut solver =
-                day10::Day10::from_default_path(&reader, 2025, day).expect("Failed to load input");
-            solver.solve();
-        }
-        11 => {
-            let mut solver =
-                day11::Day11::from_default_path(&reader, 2025, day).expect("Failed to load input");
-            solver.solve();
-        }
-        12 => {
-            let mut solver =
-                day12::Day12::from_default_path(&reader, 2025, day).expect("Failed to load input");
-            solver.solve();
-        }
-        _ => println!("Day {} not implemented", day),
     }
 }